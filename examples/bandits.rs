@@ -0,0 +1,74 @@
+use learnwell::{
+    agent::{
+        bandit::{EpsilonGreedy, LinUcb, Prior, ThompsonSampling, Ucb1},
+        Agent,
+    },
+    envs::bandit::{Arm, Bandit, BanditConfig, ContextualBandit},
+    runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//compares the cumulative regret of bandit agents, where every epoch is 1 pull
+fn main() {
+    fastrand::seed(0);
+    let pulls = 2000;
+    let greedy = || EpsilonGreedy::new(DecliningRandom::new(0, 0.1));
+
+    println!("10 armed testbed");
+    let env = Bandit::default();
+    run("epsilon greedy", greedy(), &env, pulls);
+    run("UCB1", Ucb1::new(2.), &env, pulls);
+    run(
+        "Thompson sampling",
+        ThompsonSampling::new(Prior::Gaussian),
+        &env,
+        pulls,
+    );
+
+    println!("\nclick through rates");
+    let env = Bandit::bernoulli(&[0.02, 0.03, 0.05, 0.04]);
+    run("epsilon greedy", greedy(), &env, pulls);
+    run("UCB1", Ucb1::new(2f64.sqrt()), &env, pulls);
+    run(
+        "Thompson sampling",
+        ThompsonSampling::new(Prior::Beta),
+        &env,
+        pulls,
+    );
+
+    println!("\ndrifting arms");
+    let env = Bandit::new(BanditConfig {
+        arms: (0..10)
+            .map(|_| Arm::Gaussian { mean: 0., std: 1. })
+            .collect(),
+        drift: 0.01,
+    });
+    run("epsilon greedy", greedy(), &env, pulls);
+    run(
+        "epsilon greedy with step size",
+        greedy().with_step_size(0.1),
+        &env,
+        pulls,
+    );
+
+    println!("\ncontextual");
+    let env = ContextualBandit::default();
+    let (_, metrics) = Runner::run_with_metrics(LinUcb::new(1.), env.clone(), pulls);
+    print_regrets("LinUCB", &metrics.regrets);
+    let (_, metrics) = Runner::run_with_metrics(greedy(), env, pulls);
+    print_regrets("epsilon greedy, ignoring features", &metrics.regrets);
+}
+
+/// Runs on a copy of `env`, so every agent has the same arms
+fn run(name: &str, agent: impl Agent<(), usize>, env: &Bandit, pulls: usize) {
+    let (_, metrics) = Runner::run_with_metrics(agent, env.clone(), pulls);
+    print_regrets(name, &metrics.regrets);
+}
+
+/// `regrets` is the cumulative regret after each pull
+fn print_regrets(name: &str, regrets: &[f64]) {
+    println!(
+        "{name:<35} regret after 100 pulls: {:>6.1}  500: {:>6.1}  2000: {:>6.1}",
+        regrets[99], regrets[499], regrets[1999]
+    );
+}
@@ -0,0 +1,67 @@
+use learnwell::{
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::{Loss, Mlp, Optimizer},
+        qlearning::QLearning,
+    },
+    envs::{acrobot::Acrobot, cartpole::CartPole, mountaincar::MountainCar},
+    runner::{Metrics, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//QLearning on the binned state of each classic control environment.
+//With --dqn, DeepQLearning balances CartPole from its position, velocity, angle and angular velocity
+fn main() {
+    fastrand::seed(0);
+    if std::env::args().any(|x| x == "--dqn") {
+        let epochs = 400;
+        let mut env = CartPole::default();
+        let config = DeepQLearningConfig {
+            nn_learning_rate: 0.001,
+            nn_batch_size: 32,
+            train_steps: 1,
+            copy_nn_steps: 500,
+            replay_size: 32,
+            q_learning_rate: 1.,
+            discount: 0.99,
+            history_size: 50_000,
+        };
+        let nn = |shape: &Vec<usize>, lr: f32| {
+            Mlp::new(shape, lr)
+                .with_optimizer(Optimizer::adam())
+                .with_loss(Loss::Huber(1.))
+        };
+        let agent = DeepQLearning::new(
+            nn,
+            &[64, 64],
+            config,
+            DecliningRandom::new(epochs / 2, 0.02),
+            &mut env,
+        );
+        let (_, metrics) = Runner::run_with_metrics(agent, env, epochs);
+        report("CartPole DQN", &metrics);
+        return;
+    }
+
+    let epochs = 3000;
+    let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+    let (_, metrics) = Runner::run_with_metrics(agent, CartPole::default(), epochs);
+    report("CartPole", &metrics);
+
+    let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+    let (_, metrics) = Runner::run_with_metrics(agent, MountainCar::default(), epochs);
+    report("MountainCar", &metrics);
+
+    let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+    let (_, metrics) = Runner::run_with_metrics(agent, Acrobot::default(), epochs);
+    report("Acrobot", &metrics);
+}
+
+fn report(name: &str, metrics: &Metrics) {
+    println!(
+        "{name}: {} epochs in {:.2}s, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.duration.as_secs_f64(),
+        metrics.mean_return(100)
+    );
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use learnwell::{
+    environment::{
+        checker::{self, CheckConfig},
+        wrappers::{ActionRepeat, FrameStack, TimeLimit},
+        Environment,
+    },
+    envs::{
+        acrobot::Acrobot, bandit::Bandit, cartpole::CartPole, gridworld::GridWorld, hike::Hike,
+        mountaincar::MountainCar, mouse::MouseEnvironment, taxi::TaxiEnvironment,
+    },
+};
+
+//checks the built in environments, then one with common mistakes
+fn main() {
+    let config = CheckConfig::default();
+    println!(
+        "taxi: {}",
+        checker::check(TaxiEnvironment::default, &config)
+    );
+    println!(
+        "mouse: {}",
+        checker::check(MouseEnvironment::default, &config)
+    );
+    println!("hike: {}", checker::check(Hike::default, &config));
+    println!(
+        "frozen lake: {}",
+        checker::check(|| GridWorld::frozen_lake(true), &config)
+    );
+    println!("cartpole: {}", checker::check(CartPole::default, &config));
+    println!(
+        "mountaincar with wrappers: {}",
+        checker::check(
+            || TimeLimit::new(
+                FrameStack::new(ActionRepeat::new(MountainCar::default(), 4), 2),
+                40
+            ),
+            &config
+        )
+    );
+    println!("acrobot: {}", checker::check(Acrobot::default, &config));
+    println!("bandit: {}", checker::check(Bandit::default, &config));
+
+    let config = CheckConfig {
+        max_steps: 1000,
+        ..Default::default()
+    };
+    println!("broken: {}", checker::check(Broken::default, &config));
+}
+
+/// Walks along a line, but never stops, gets more actions as it goes,
+/// divides by 0 at the start, and lists its actions in a different order each time it is created
+#[derive(Default)]
+struct Broken {
+    position: i32,
+    moves: HashSet<i32>,
+}
+
+impl Environment<i32, i32> for Broken {
+    fn state(&self) -> i32 {
+        self.position
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.position = 0;
+        self.moves = HashSet::from([-1, 1]);
+    }
+
+    fn all_actions(&self) -> Vec<i32> {
+        self.moves.iter().copied().collect()
+    }
+
+    fn take_action_get_reward(&mut self, action: &i32) -> f64 {
+        self.position += action;
+        if self.position > 5 {
+            self.moves.insert(2);
+        }
+        1. / self.position as f64
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        false
+    }
+}
@@ -1,3 +0,0 @@
-pub mod hike;
-pub mod mouse;
-pub mod taxi;
@@ -0,0 +1,46 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::gridworld::{GridRewards, GridWorld, GridWorldConfig},
+    runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//QLearning on FrozenLake, CliffWalking, and a map drawn here, then how well each does without exploring
+fn main() {
+    fastrand::seed(0);
+    let epochs = 5000;
+
+    let worlds = [
+        ("FrozenLake", GridWorld::frozen_lake(false)),
+        ("FrozenLake slippery", GridWorld::frozen_lake(true)),
+        ("CliffWalking", GridWorld::cliff_walking()),
+        (
+            "Custom",
+            GridWorld::new(GridWorldConfig {
+                //the long way round is better than the short way through the mud, if we avoid the hole
+                map: "\
+S~~~~~G
+.#####.
+.......
+...H..."
+                    .to_string(),
+                rewards: GridRewards {
+                    goal: 20.,
+                    ..Default::default()
+                },
+                cell_rewards: vec![('~', -4.)],
+                ..Default::default()
+            }),
+        ),
+    ];
+
+    for (name, world) in worlds {
+        let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+        let agent = Runner::run(agent, world.clone(), epochs);
+        let metrics = Runner::evaluate(&agent, world, 1000);
+        println!(
+            "{name}: average return without exploring {:.2}",
+            metrics.mean_return(1000)
+        );
+    }
+}
@@ -1,19 +1,36 @@
-use environments::hike::Hike;
 use learnwell::{
-    agent::qlearning::QLearning,
-    runner::{DisplayConfig, Runner},
+    agent::qlearning::QLearning, envs::hike::Hike, runner::Runner,
     strategy::decliningrandom::DecliningRandom,
 };
 
-mod environments;
-
+//pass `--text` to watch in the terminal instead of a window
 fn main() {
     let epochs = 700_000;
+    let agent = QLearning::new(0.2, 0.99, DecliningRandom::new(epochs, 0.005));
+
+    #[cfg(feature = "display")]
+    if !std::env::args().any(|x| x == "--text") {
+        Runner::with_display(move || {
+            let (_, metrics) = Runner::run_with_display(
+                agent,
+                Hike::default(),
+                epochs,
+                learnwell::runner::DisplayConfig {
+                    plot: true,
+                    plot_average: 1000,
+                    scale: 10,
+                    status_bar: true,
+                    wait_for_close: true,
+                    ..Default::default()
+                },
+            );
+            println!(
+                "{} epochs, average return of last 100: {:.2}",
+                metrics.epochs(),
+                metrics.mean_return(100)
+            );
+        });
+    }
 
-    Runner::run_with_display(
-        QLearning::new(0.2, 0.99, DecliningRandom::new(epochs, 0.005)),
-        Hike::new(),
-        epochs,
-        DisplayConfig::default(),
-    );
+    Runner::run_with_text(agent, Hike::default(), epochs, Default::default());
 }
@@ -0,0 +1,60 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    environment::wrappers::PotentialShaping,
+    envs::{
+        hike::{Hike, HikeConfig, HikeRewards, HikeState},
+        Point,
+    },
+    runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//Hike takes the distance left to the end off every step, which leads QLearning into dead ends close to the end.
+//Without it rewards are sparse. Potential based shaping guides towards the end without changing the best route
+fn main() {
+    let epochs = 100_000;
+    let discount = 0.99;
+    let agent = || QLearning::new(0.2, discount, DecliningRandom::new(epochs, 0.005));
+    let sparse = HikeConfig {
+        rewards: HikeRewards {
+            distance: 0.,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let end = sparse.end;
+
+    fastrand::seed(0);
+    let mut hike = Hike::default();
+    Runner::run(agent(), &mut hike, epochs);
+    report("distance taken off each step", &hike, &end);
+
+    fastrand::seed(0);
+    let mut hike = Hike::new(sparse.clone());
+    Runner::run(agent(), &mut hike, epochs);
+    report("sparse", &hike, &end);
+
+    fastrand::seed(0);
+    let potential = move |state: &HikeState| -10. * state.current.distance(&end);
+    let mut shaped = PotentialShaping::new(Hike::new(sparse), discount, potential);
+    let (_, metrics) = Runner::run_with_metrics(agent(), &mut shaped, epochs);
+    report("sparse with potential shaping", shaped.inner(), &end);
+    println!(
+        "average return of last 100 with shaping: {:.1}, without: {:.1}",
+        metrics.mean_return(100),
+        metrics.mean_raw_return(100)
+    );
+}
+
+/// Distance is from the end of the best route, which got closest to the end
+fn report(name: &str, hike: &Hike, end: &Point) {
+    match hike.best() {
+        Some(steps) => println!("{name:<30} reached the end in {steps} steps"),
+        None => println!(
+            "{name:<30} got within {:.1} of the end",
+            hike.best_route()
+                .last()
+                .map_or(f64::NAN, |p| p.distance(end))
+        ),
+    }
+}
@@ -1,8 +1,7 @@
-use environments::mouse::MouseEnvironment;
 use learnwell::{
-    agent::qlearning::QLearning, runner::Runner, strategy::decliningrandom::DecliningRandom,
+    agent::qlearning::QLearning, envs::mouse::MouseEnvironment, runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
 };
-mod environments;
 
 fn main() {
     let epochs = 500;
@@ -13,5 +12,10 @@ fn main() {
         DecliningRandom::new((epochs as f64 * 0.9) as usize, 0.005),
     );
 
-    Runner::run(agent, MouseEnvironment::default(), epochs);
+    let (_, metrics) = Runner::run_with_metrics(agent, MouseEnvironment::default(), epochs);
+    println!(
+        "{} epochs, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.mean_return(100)
+    );
 }
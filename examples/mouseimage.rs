@@ -1,15 +1,15 @@
-mod environments;
 mod nnbackends;
 
-use environments::mouse::MouseEnvironment;
 use learnwell::{
-    agent::deepqlearning::{DeepQLearning, DeepQLearningConfig},
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::Mlp,
+    },
+    envs::mouse::MouseEnvironment,
     runner::Runner,
     strategy::decliningrandom::DecliningRandom,
 };
 
-use nnbackends::runntbackend::RunntBackend;
-
 fn main() {
     let epochs = 3000;
     fastrand::seed(0);
@@ -25,7 +25,11 @@ fn main() {
         history_size: 10000,
     };
 
-    let nn = |shape: &Vec<usize>, lr: f32| RunntBackend::new(&shape, lr);
+    let nn = |shape: &Vec<usize>, lr: f32| Mlp::new(shape, lr);
+
+    //uncomment to use runnt
+    //use nnbackends::runntbackend::RunntBackend;
+    //let nn = |shape: &Vec<usize>, lr: f32| RunntBackend::new(shape, lr);
 
     //uncomment to use torch
     //use nnbackends::tchbackend::TchBackend;
@@ -39,5 +43,10 @@ fn main() {
         &mut env,
     );
 
-    Runner::run(agent, env, epochs);
+    let (_, metrics) = Runner::run_with_metrics(agent, env, epochs);
+    println!(
+        "{} epochs, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.mean_return(100)
+    );
 }
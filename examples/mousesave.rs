@@ -0,0 +1,43 @@
+use learnwell::{
+    agent::{qlearning::QLearning, Policy},
+    environment::Environment,
+    envs::mouse::{MouseAction, MouseEnvironment, MouseState},
+    persist::Format,
+    runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//run with `cargo run --example mousesave --features serde`
+fn main() {
+    let epochs = 500;
+    let strategy = || DecliningRandom::new((epochs as f64 * 0.9) as usize, 0.005);
+
+    let agent = Runner::run(
+        QLearning::new(0.5, 0.5, strategy()),
+        MouseEnvironment::default(),
+        epochs,
+    );
+
+    let path = std::env::temp_dir().join("mouse.qtable");
+    agent.save(&path, Format::Binary).unwrap();
+    agent
+        .save(path.with_extension("json"), Format::Json)
+        .unwrap();
+
+    //load into a new agent, which acts the same as the trained one
+    let loaded: QLearning<MouseState, MouseAction> =
+        QLearning::load(&path, Format::Binary, strategy()).unwrap();
+
+    let env = MouseEnvironment::default();
+    let state = env.state();
+    let actions = env.all_actions();
+    assert_eq!(
+        agent.action_values(&state, &actions),
+        loaded.action_values(&state, &actions)
+    );
+    println!(
+        "Loaded agent from {} picks {:?}",
+        path.display(),
+        loaded.act(&state, &actions)
+    );
+}
@@ -0,0 +1,44 @@
+use learnwell::{
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::Mlp,
+    },
+    environment::vecenv::VecEnv,
+    envs::mouse::MouseEnvironment,
+    runner::Runner,
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//steps 8 mice together, so the network is called once for all of them
+fn main() {
+    let epochs = 3000;
+    fastrand::seed(0);
+    let mut envs = VecEnv::from_fn(8, MouseEnvironment::default);
+    let config = DeepQLearningConfig {
+        nn_learning_rate: 0.1,
+        replay_size: 32,
+        nn_batch_size: 16,
+        discount: 0.9,
+        train_steps: 1,
+        copy_nn_steps: 20,
+        q_learning_rate: 0.1,
+        history_size: 10000,
+    };
+
+    let nn = |shape: &Vec<usize>, lr: f32| Mlp::new(shape, lr);
+    let agent = DeepQLearning::new(
+        nn,
+        &[32],
+        config,
+        DecliningRandom::new((epochs as f64 * 0.9) as usize, 0.005),
+        &mut envs.envs_mut()[0],
+    );
+
+    let (_, metrics) = Runner::run_vec(agent, envs, epochs);
+    println!(
+        "{} epochs in {:.2}s, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.duration.as_secs_f64(),
+        metrics.mean_return(100)
+    );
+}
@@ -0,0 +1,32 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::multitaxi::MultiTaxi,
+    runner::{Metrics, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//3 taxis on the same map, each learning on its own, then all sharing 1 qtable
+fn main() {
+    fastrand::seed(0);
+    let epochs = 5000;
+    let agent = || QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01));
+
+    let agents = (0..3).map(|_| agent()).collect();
+    let (_, metrics) = Runner::run_independent(agents, MultiTaxi::default(), epochs);
+    report("independent", &metrics);
+
+    let (_, metrics) = Runner::run_shared(agent(), MultiTaxi::default(), epochs);
+    report("shared", &metrics);
+}
+
+fn report(name: &str, metrics: &[Metrics]) {
+    let returns: Vec<String> = metrics
+        .iter()
+        .map(|m| format!("{:.1}", m.mean_return(100)))
+        .collect();
+    println!(
+        "{name}: average return of last 100 epochs for each taxi {} in {:.2}s",
+        returns.join(", "),
+        metrics[0].duration.as_secs_f64()
+    );
+}
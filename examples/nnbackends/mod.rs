@@ -1,2 +1,5 @@
+//examples use the built in backend, these show how to implement your own
+#![allow(dead_code)]
+
 pub mod runntbackend;
 pub mod tchbackend;
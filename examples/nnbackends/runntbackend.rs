@@ -5,7 +5,7 @@ pub struct RunntBackend {
 }
 
 impl RunntBackend {
-    pub fn new(network_shape: &Vec<usize>, learning_rate: f32) -> Self {
+    pub fn new(network_shape: &[usize], learning_rate: f32) -> Self {
         Self {
             nn: runnt::nn::NN::new(network_shape)
                 .with_hidden_type(runnt::activation::ActivationType::Sigmoid)
@@ -16,11 +16,7 @@ impl RunntBackend {
 }
 
 impl NNBackend for RunntBackend {
-    fn update_from(&mut self, other: &Self) {
-        self.nn.set_weights(&other.nn.get_weights());
-    }
-
-    fn forward(&mut self, input: &[f32]) -> Vec<f32> {
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
         self.nn.forward(input)
     }
 
@@ -30,8 +26,16 @@ impl NNBackend for RunntBackend {
             self.nn.fit_one(inp, &out);
         }*/
 
-        let is = inputs.iter().map(|x| x).collect::<Vec<_>>();
-        let os = outputs.iter().map(|x| x).collect::<Vec<_>>();
+        let is = inputs.iter().collect::<Vec<_>>();
+        let os = outputs.iter().collect::<Vec<_>>();
         self.nn.fit(&is, &os, batch_size);
     }
+
+    fn weights(&self) -> Vec<f32> {
+        self.nn.get_weights()
+    }
+
+    fn set_weights(&mut self, weights: &[f32]) {
+        self.nn.set_weights(weights);
+    }
 }
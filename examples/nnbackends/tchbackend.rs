@@ -53,11 +53,16 @@ impl TchBackend {
 }
 
 impl NNBackend for TchBackend {
-    fn forward(&mut self, input: &[f32]) -> Vec<f32> {
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
         let t = self.net.forward(&Tensor::of_slice(input));
         t.into()
     }
 
+    fn forward_batch(&self, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let t = self.net.forward(&Tensor::of_slice2(inputs));
+        Vec::<Vec<f32>>::from(&t)
+    }
+
     fn fit(&mut self, inputs: &[Vec<f32>], outputs: &[Vec<f32>], _batch_size: usize) {
         /*for (inp, out) in inputs.iter().zip(outputs) {
             let loss = self
@@ -75,10 +80,31 @@ impl NNBackend for TchBackend {
         self.opt.backward_step(&loss);
     }
 
-    fn update_from(&mut self, other: &Self) {
-        let path = std::path::Path::new("c:/temp/tch.txt");
-        other.vs.save(path).unwrap();
-        self.vs.load(path).unwrap();
+    fn weights(&self) -> Vec<f32> {
+        self.sorted_variables()
+            .iter()
+            .flat_map(|t| Vec::<f32>::from(t.flatten(0, -1)))
+            .collect()
+    }
+
+    fn set_weights(&mut self, weights: &[f32]) {
+        let mut start = 0;
+        tch::no_grad(|| {
+            for mut t in self.sorted_variables() {
+                let end = start + t.numel();
+                t.copy_(&Tensor::of_slice(&weights[start..end]).view_as(&t));
+                start = end;
+            }
+        });
+    }
+}
+
+impl TchBackend {
+    /// variables are stored in a hashmap, so sort by name to always get the same order
+    fn sorted_variables(&self) -> Vec<Tensor> {
+        let mut vars: Vec<(String, Tensor)> = self.vs.variables().into_iter().collect();
+        vars.sort_by(|a, b| a.0.cmp(&b.0));
+        vars.into_iter().map(|x| x.1).collect()
     }
 }
 */
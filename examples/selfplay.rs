@@ -0,0 +1,71 @@
+use learnwell::{
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::{Loss, Mlp, Optimizer},
+        qlearning::QLearning,
+    },
+    environment::turnbased::CurrentPlayer,
+    envs::{connectfour::ConnectFour, tictactoe::TicTacToe},
+    runner::{Metrics, Runner, SelfPlayConfig},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//agents trained against copies of themselves, then played against random moves.
+//QLearning learns Tic-Tac-Toe, but Connect Four has too many positions for a qtable so we use DQN, which takes a few minutes
+fn main() {
+    fastrand::seed(0);
+
+    let config = SelfPlayConfig::default();
+    let epochs = 200_000;
+    let agent = QLearning::new(0.2, 0.99, DecliningRandom::new(epochs, 0.05));
+    let (agent, _) = Runner::run_self_play(agent, TicTacToe::default(), epochs, &config);
+    let metrics = Runner::evaluate_game(&agent, TicTacToe::default(), 1000, &config);
+    print_results("Tic-Tac-Toe", &metrics);
+
+    //the network needs the same actions every step, so it learns not to play full columns
+    let config = SelfPlayConfig {
+        snapshot_every: 500,
+        valid_actions_only: false,
+        ..Default::default()
+    };
+    let epochs = 10_000;
+    let mut game = ConnectFour::default();
+    let nn = |shape: &Vec<usize>, lr: f32| {
+        Mlp::new(shape, lr)
+            .with_optimizer(Optimizer::adam())
+            .with_loss(Loss::Huber(1.))
+    };
+    let dqn_config = DeepQLearningConfig {
+        nn_learning_rate: 0.001,
+        nn_batch_size: 32,
+        train_steps: 1,
+        copy_nn_steps: 500,
+        replay_size: 32,
+        q_learning_rate: 1.,
+        discount: 0.99,
+        history_size: 50_000,
+    };
+    let agent = DeepQLearning::new(
+        nn,
+        &[128, 64],
+        dqn_config,
+        DecliningRandom::new(epochs / 2, 0.05),
+        &mut CurrentPlayer(&mut game),
+    );
+    let (agent, _) = Runner::run_self_play(agent, game, epochs, &config);
+    let metrics = Runner::evaluate_game(&agent, ConnectFour::default(), 1000, &config);
+    print_results("Connect Four", &metrics);
+}
+
+fn print_results(name: &str, metrics: &Metrics) {
+    let percent = |f: fn(f64) -> bool| {
+        let count = metrics.episode_returns.iter().filter(|&&r| f(r)).count();
+        count as f64 / metrics.epochs() as f64 * 100.
+    };
+    println!(
+        "{name} against random moves: won {:.1}%, drew {:.1}%, lost {:.1}%",
+        percent(|r| r > 0.),
+        percent(|r| r == 0.),
+        percent(|r| r < 0.)
+    );
+}
@@ -1,15 +1,36 @@
-use environments::taxi::TaxiEnvironment;
 use learnwell::{
-    agent::qlearning::QLearning, runner::Runner, strategy::decliningrandom::DecliningRandom,
+    agent::{qlearning::QLearning, Policy},
+    environment::Environment,
+    envs::taxi::TaxiEnvironment,
+    runner::{Runner, TextConfig},
+    strategy::decliningrandom::DecliningRandom,
 };
-mod environments;
 
 fn main() {
     // now we train
     let epochs = 400;
-    Runner::run(
-        QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01)),
-        TaxiEnvironment::default(),
-        epochs,
-    );
+    let agent = QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01));
+    let agent = if std::env::args().any(|x| x == "--text") {
+        //pass `--text` to watch the last few epochs in the terminal
+        let config = TextConfig {
+            step_time: std::time::Duration::from_millis(200),
+            step_time_start: epochs - 5,
+            ..Default::default()
+        };
+        Runner::run_with_text(agent, TaxiEnvironment::default(), epochs, config)
+    } else {
+        Runner::run(agent, TaxiEnvironment::default(), epochs)
+    };
+
+    // then we use the trained agent without learning
+    let mut env = TaxiEnvironment::default();
+    env.reset(1);
+    let mut reward = 0.;
+    let mut step = 0;
+    while !env.should_stop(step) {
+        step += 1;
+        let action = agent.act(&env.state(), &env.all_actions());
+        reward += env.take_action_get_reward(&action);
+    }
+    println!("Trained agent took {step} steps with reward {reward}");
 }
@@ -0,0 +1,29 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::taxi::TaxiEnvironment,
+    runner::{CheckpointConfig, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//run with `cargo run --example taxicheckpoint --features serde`
+fn main() {
+    let epochs = 400;
+    let agent = || QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01));
+    let config = CheckpointConfig {
+        path: std::env::temp_dir().join("taxi.checkpoint"),
+        every: 50,
+    };
+    let _ = std::fs::remove_file(&config.path);
+
+    //uninterrupted run
+    fastrand::seed(0);
+    let full = Runner::run(agent(), TaxiEnvironment::default(), epochs);
+
+    //interrupted after 200 epochs, then resumed from the checkpoint with a new agent
+    fastrand::seed(0);
+    Runner::run_with_checkpoints(agent(), TaxiEnvironment::default(), 200, &config).unwrap();
+    let resumed = Runner::resume(agent(), TaxiEnvironment::default(), epochs, &config).unwrap();
+
+    assert!(full.qtable == resumed.qtable);
+    println!("Resumed run matches uninterrupted run");
+}
@@ -1,14 +1,15 @@
 use std::time::Duration;
 
-use environments::taxi::TaxiEnvironment;
 use learnwell::{
-    agent::deepqlearning::{DeepQLearning, DeepQLearningConfig},
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::Mlp,
+    },
+    envs::taxi::TaxiEnvironment,
     runner::{DisplayConfig, Runner},
     strategy::decliningrandom::DecliningRandom,
 };
-mod environments;
 mod nnbackends;
-use nnbackends::runntbackend::RunntBackend;
 
 fn main() {
     let epochs = 5000;
@@ -26,7 +27,8 @@ fn main() {
     };
 
     //function to create Neural network
-    let nnf = |shape: &Vec<usize>, lr: f32| RunntBackend::new(&shape, lr);
+    let nnf = |shape: &Vec<usize>, lr: f32| Mlp::new(shape, lr);
+    //let nnf = |shape: &Vec<usize>, lr: f32| nnbackends::runntbackend::RunntBackend::new(shape, lr);
     //let nnf = |shape: &Vec<usize>, lr: f32| TchBackend::new(&shape, lr);
 
     let agent = DeepQLearning::new(
@@ -38,14 +40,25 @@ fn main() {
     );
 
     //Runner::run(agent, env, epochs);
-    Runner::run_with_display(
-        agent,
-        env,
-        epochs,
-        DisplayConfig {
-            step_time: Duration::from_millis(50),
-            step_time_start: 4990,
-            ..Default::default()
-        },
-    );
+    Runner::with_display(move || {
+        let (_, metrics) = Runner::run_with_display(
+            agent,
+            env,
+            epochs,
+            DisplayConfig {
+                step_time: Duration::from_millis(50),
+                step_time_start: 4990,
+                plot: true,
+                scale: 20,
+                grid: true,
+                status_bar: true,
+                wait_for_close: true,
+                ..Default::default()
+            },
+        );
+        println!(
+            "average return of last 100 epochs: {:.2}",
+            metrics.mean_return(100)
+        );
+    });
 }
@@ -0,0 +1,29 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::taxi::TaxiEnvironment,
+    runner::{ParallelConfig, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//several workers step their own taxi, while the agent learns from all of their steps
+fn main() {
+    let epochs = 2000;
+    let config = ParallelConfig {
+        workers: 4,
+        sync_steps: 500,
+        ..Default::default()
+    };
+    let (_, metrics) = Runner::run_parallel(
+        QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01)),
+        TaxiEnvironment::default(),
+        epochs,
+        config,
+        || DecliningRandom::new(epochs, 0.01),
+    );
+    println!(
+        "{} epochs in {:.2}s, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.duration.as_secs_f64(),
+        metrics.mean_return(100)
+    );
+}
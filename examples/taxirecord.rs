@@ -0,0 +1,26 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::taxi::TaxiEnvironment,
+    runner::{RecordConfig, RecordEpochs, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//run with `cargo run --example taxirecord --features record`
+//records the last few epochs to gifs without opening a window
+fn main() {
+    let epochs = 400;
+    let config = RecordConfig {
+        dir: std::env::temp_dir().join("taxi"),
+        epochs: RecordEpochs::List(vec![1, 398, 399, 400]),
+        scale: 20,
+        ..Default::default()
+    };
+    Runner::run_with_recording(
+        QLearning::new(0.1, 0.98, DecliningRandom::new(epochs, 0.01)),
+        TaxiEnvironment::default(),
+        epochs,
+        &config,
+    )
+    .unwrap();
+    println!("Saved to {}", config.dir.display());
+}
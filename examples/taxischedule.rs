@@ -0,0 +1,58 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::taxi::TaxiEnvironment,
+    strategy::decliningrandom::DecliningRandom,
+    sweep::{self, HalvingConfig, HyperbandConfig, PbtConfig, Search, SearchSpace},
+};
+
+//successive halving over a grid of alpha and gamma, then hyperband and population based training over the same ranges
+fn main() {
+    let space = SearchSpace::new()
+        .linear("alpha", 0.05, 0.5, 4)
+        .linear("gamma", 0.8, 0.98, 4);
+
+    let config = HalvingConfig {
+        epochs: 50,
+        ..Default::default()
+    };
+    let results = sweep::successive_halving(&space, Search::Grid, &config, |params| {
+        let agent = QLearning::new(
+            params.get("alpha"),
+            params.get("gamma"),
+            DecliningRandom::new(400, 0.01),
+        );
+        (agent, TaxiEnvironment::default())
+    });
+    println!("successive halving\n{results}");
+
+    let config = HyperbandConfig {
+        epochs: 50,
+        ..Default::default()
+    };
+    let results = sweep::hyperband(&space, &config, |params| {
+        let agent = QLearning::new(
+            params.get("alpha"),
+            params.get("gamma"),
+            DecliningRandom::new(400, 0.01),
+        );
+        (agent, TaxiEnvironment::default())
+    });
+    println!("hyperband\n{results}");
+
+    let config = PbtConfig {
+        rounds: 8,
+        epochs: 50,
+        ..Default::default()
+    };
+    let results = sweep::population_based(&space, &config, |params| {
+        let agent = QLearning::new(
+            params.get("alpha"),
+            params.get("gamma"),
+            DecliningRandom::new(400, 0.01),
+        );
+        (agent, TaxiEnvironment::default())
+    })
+    .unwrap();
+    println!("population based training\n{results}");
+    println!("best: {}", results.best().params);
+}
@@ -0,0 +1,34 @@
+use learnwell::{
+    agent::qlearning::QLearning,
+    envs::taxi::TaxiEnvironment,
+    strategy::decliningrandom::DecliningRandom,
+    sweep::{self, Search, SearchSpace, SweepConfig},
+};
+
+//tries every combination of alpha and gamma with 3 seeds each, and prints them best first
+fn main() {
+    let epochs = 400;
+    let space = SearchSpace::new()
+        .values("alpha", &[0.05, 0.1, 0.3])
+        .linear("gamma", 0.8, 0.98, 3);
+    let config = SweepConfig {
+        epochs,
+        ..Default::default()
+    };
+    let results = sweep::run(
+        &space,
+        Search::Grid,
+        &config,
+        |params| {
+            let agent = QLearning::new(
+                params.get("alpha"),
+                params.get("gamma"),
+                DecliningRandom::new(epochs, 0.01),
+            );
+            (agent, TaxiEnvironment::default())
+        },
+        |_, metrics| metrics.mean_return(100),
+    );
+    println!("{results}");
+    println!("best: {}", results.best().params);
+}
@@ -0,0 +1,69 @@
+use learnwell::{
+    agent::{
+        deepqlearning::{DeepQLearning, DeepQLearningConfig},
+        nnbackend::mlp::{Loss, Mlp, Optimizer},
+        qlearning::QLearning,
+    },
+    environment::wrappers::{
+        ActionRepeat, FrameStack, ObservationNormalize, RewardScale, TimeLimit,
+    },
+    envs::{cartpole::CartPole, mountaincar::MountainCar},
+    runner::{Metrics, Runner},
+    strategy::decliningrandom::DecliningRandom,
+};
+
+//wrappers change an environment without changing its code, and stack.
+//Repeating each action lets QLearning see MountainCar build momentum, and DQN learns CartPole
+//from normalized observations of the last 2 steps, with rewards scaled down and epochs cut to 200 steps
+fn main() {
+    fastrand::seed(0);
+    let epochs = 3000;
+
+    let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+    let (_, metrics) = Runner::run_with_metrics(agent, MountainCar::default(), epochs);
+    report("MountainCar", &metrics, 1.);
+
+    let env = ActionRepeat::new(MountainCar::default(), 4);
+    let agent = QLearning::new(0.1, 0.99, DecliningRandom::new(epochs, 0.01));
+    let (_, metrics) = Runner::run_with_metrics(agent, env, epochs);
+    report("MountainCar, actions repeated 4 times", &metrics, 1.);
+
+    let epochs = 300;
+    let scale = 0.1;
+    let env = FrameStack::new(ObservationNormalize::new(CartPole::default()), 2);
+    let mut env = TimeLimit::new(RewardScale::new(env, scale), 200);
+    let config = DeepQLearningConfig {
+        nn_learning_rate: 0.001,
+        nn_batch_size: 32,
+        train_steps: 1,
+        copy_nn_steps: 500,
+        replay_size: 32,
+        q_learning_rate: 1.,
+        discount: 0.99,
+        history_size: 50_000,
+    };
+    let nn = |shape: &Vec<usize>, lr: f32| {
+        Mlp::new(shape, lr)
+            .with_optimizer(Optimizer::adam())
+            .with_loss(Loss::Huber(1.))
+    };
+    let agent = DeepQLearning::new(
+        nn,
+        &[64, 64],
+        config,
+        DecliningRandom::new(epochs / 2, 0.02),
+        &mut env,
+    );
+    let (_, metrics) = Runner::run_with_metrics(agent, env, epochs);
+    report("CartPole DQN, normalized and stacked", &metrics, scale);
+}
+
+/// `scale` undoes reward scaling, to compare with the environment's own rewards
+fn report(name: &str, metrics: &Metrics, scale: f64) {
+    println!(
+        "{name}: {} epochs in {:.2}s, average return of last 100: {:.2}",
+        metrics.epochs(),
+        metrics.duration.as_secs_f64(),
+        metrics.mean_return(100) / scale
+    );
+}
@@ -1,5 +1,16 @@
-use crate::{environment::Environment, progress::Progress};
+#[cfg(feature = "serde")]
+use crate::persist::PersistError;
+use crate::{
+    environment::{
+        vecenv::{VecEnv, VecStep},
+        Environment,
+    },
+    progress::Progress,
+};
+#[cfg(feature = "serde")]
+use std::io::{Read, Write};
 
+pub mod bandit;
 pub mod deepqlearning;
 pub mod nnbackend;
 pub mod qlearning;
@@ -14,4 +25,91 @@ pub trait Agent<S, A> {
         best: Option<A>, //best based on qlearning
         progress: Progress,
     ) -> A;
+
+    /// Current chance of exploring, used for metrics. `None` if unknown
+    fn exploration(&self) -> Option<f64> {
+        None
+    }
+}
+
+///agent which steps every environment of a `VecEnv` together, so it can batch network calls
+pub trait VecAgent<S, A>: Agent<S, A> {
+    /// Steps every environment once.
+    /// `progress.cumulative_steps` includes the steps about to be taken
+    fn step_vec<E: Environment<S, A>>(
+        &mut self,
+        progress: Progress,
+        envs: &mut VecEnv<E>,
+    ) -> VecStep;
+}
+
+///agent which learns from steps taken by workers in other threads, see `Runner::run_parallel`
+pub trait ParallelAgent<S, A>: Agent<S, A> {
+    /// What the agent learns from e.g. the state, or the image for deep learning
+    type Observation: Send;
+    /// Read only copy of the agent, used by workers to pick actions
+    type Actor: Send + Sync;
+
+    fn observe(env: &mut dyn Environment<S, A>) -> Self::Observation;
+    fn actor(&self) -> Self::Actor;
+    /// Best of `actions` according to `actor`, or `None` if it does not know yet
+    fn best_action(
+        actor: &Self::Actor,
+        observation: &Self::Observation,
+        actions: &[A],
+    ) -> Option<A>;
+    fn learn(&mut self, transition: Transition<Self::Observation, A>, progress: Progress);
+}
+
+///agent which picks an action and learns from it in separate calls, so several agents can act in the same step.
+///See `Runner::run_independent` and `Runner::run_shared`
+pub trait JointAgent<S, A>: ParallelAgent<S, A> {
+    /// Best of `actions` for `observation`, or `None` if it does not know yet.
+    /// The runner explores with `Agent::pick_action`, and learns with `ParallelAgent::learn`
+    fn best(&self, observation: &Self::Observation, actions: &[A]) -> Option<A>;
+}
+
+/// One step taken by a worker
+pub struct Transition<O, A> {
+    pub state: O,
+    pub action: A,
+    /// position of `action` in `Environment::all_actions`
+    pub action_index: usize,
+    pub reward: f64,
+    pub next_state: O,
+    /// the epoch ended, by reaching a terminal state or being cut short
+    pub done: bool,
+    /// `next_state` is terminal, see `Environment::terminated`
+    pub terminated: bool,
+}
+
+///agent which can save everything it needs to continue training, used by `Runner` checkpoints
+#[cfg(feature = "serde")]
+pub trait Checkpoint {
+    fn save_checkpoint(&self, writer: &mut dyn Write) -> Result<(), PersistError>;
+    /// Restores into an agent created the same way as the saved one.
+    /// Hyperparameters the agent was created with are kept, e.g. for population based training
+    fn load_checkpoint(&mut self, reader: &mut dyn Read) -> Result<(), PersistError>;
+}
+
+///read only access to a trained agent, so we can query it without learning
+pub trait Policy<S, A> {
+    /// Estimated value of each of `actions` in `state`, in the same order as `actions`
+    fn action_values(&self, state: &S, actions: &[A]) -> Vec<f64>;
+
+    /// Best of `actions` in `state`
+    /// Panics if `actions` is empty
+    fn act(&self, state: &S, actions: &[A]) -> A
+    where
+        A: Clone,
+    {
+        let values = self.action_values(state, actions);
+        let best = values
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|x| x.0)
+            .expect("no actions to choose from");
+        actions[best].clone()
+    }
 }
@@ -0,0 +1,442 @@
+//! Agents for bandits, where every epoch is a single step so there is no next state to learn from.
+//! They keep estimates for each action by its position in `Environment::all_actions`,
+//! so the environment must always return the same actions in the same order
+
+use crate::{
+    agent::{Agent, Policy},
+    environment::Environment,
+    progress::Progress,
+    random::{beta, normal},
+    strategy::ExploreStrategy,
+};
+
+/// Picks the action with the best average reward, and explores with a strategy e.g. `DecliningRandom`
+pub struct EpsilonGreedy<A> {
+    strategy: Box<dyn ExploreStrategy<A> + Send>,
+    step_size: Option<f64>,
+    counts: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl<A> EpsilonGreedy<A> {
+    pub fn new(strategy: impl ExploreStrategy<A> + Send + 'static) -> Self {
+        Self {
+            strategy: Box::new(strategy),
+            step_size: None,
+            counts: vec![],
+            values: vec![],
+        }
+    }
+
+    /// Moves estimates towards each reward by `step_size` e.g. 0.1, instead of averaging all rewards.
+    /// Recent rewards count more, so it can follow arms which drift
+    pub fn with_step_size(mut self, step_size: f64) -> Self {
+        self.step_size = Some(step_size);
+        self
+    }
+
+    /// Estimated value of each action
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+impl<S, A: Clone + PartialEq> Agent<S, A> for EpsilonGreedy<A> {
+    fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
+        let actions = env.all_actions();
+        if actions.is_empty() {
+            return true;
+        }
+        resize(&mut self.counts, actions.len());
+        resize(&mut self.values, actions.len());
+        let best = self
+            .counts
+            .iter()
+            .any(|&n| n > 0)
+            .then(|| actions[best_index(&self.values)].clone());
+        let action = self.strategy.pick_action(&actions, best, progress);
+        let index = actions.iter().position(|a| *a == action).unwrap_or(0);
+
+        let reward = env.take_action_get_reward(&action);
+        self.counts[index] += 1;
+        let step_size = self.step_size.unwrap_or(1. / self.counts[index] as f64);
+        self.values[index] += step_size * (reward - self.values[index]);
+
+        env.should_stop(progress.epoch_step)
+    }
+
+    fn pick_action(&mut self, actions: &[A], best: Option<A>, progress: Progress) -> A {
+        self.strategy.pick_action(actions, best, progress)
+    }
+
+    fn exploration(&self) -> Option<f64> {
+        self.strategy.exploration()
+    }
+}
+
+impl<S, A> Policy<S, A> for EpsilonGreedy<A> {
+    fn action_values(&self, _state: &S, actions: &[A]) -> Vec<f64> {
+        values_of(&self.values, actions.len())
+    }
+}
+
+/// Upper confidence bound: picks the action with the best average reward plus a bonus
+/// which is larger for actions tried less often. Tries every action once first
+pub struct Ucb1 {
+    c: f64,
+    counts: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl Ucb1 {
+    /// `c` is how much to explore, e.g. `2f64.sqrt()` for rewards between 0 and 1
+    pub fn new(c: f64) -> Self {
+        Self {
+            c,
+            counts: vec![],
+            values: vec![],
+        }
+    }
+
+    /// Average reward of each action
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+impl<S, A: Clone> Agent<S, A> for Ucb1 {
+    fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
+        let actions = env.all_actions();
+        if actions.is_empty() {
+            return true;
+        }
+        resize(&mut self.counts, actions.len());
+        resize(&mut self.values, actions.len());
+        let total: usize = self.counts.iter().sum();
+        let index = match self.counts.iter().position(|&n| n == 0) {
+            Some(untried) => untried,
+            None => {
+                let bounds: Vec<f64> = self
+                    .values
+                    .iter()
+                    .zip(&self.counts)
+                    .map(|(v, &n)| v + self.c * ((total as f64).ln() / n as f64).sqrt())
+                    .collect();
+                best_index(&bounds)
+            }
+        };
+
+        let reward = env.take_action_get_reward(&actions[index]);
+        self.counts[index] += 1;
+        self.values[index] += (reward - self.values[index]) / self.counts[index] as f64;
+
+        env.should_stop(progress.epoch_step)
+    }
+
+    fn pick_action(&mut self, actions: &[A], best: Option<A>, _progress: Progress) -> A {
+        best_or_random(actions, best)
+    }
+}
+
+impl<S, A> Policy<S, A> for Ucb1 {
+    fn action_values(&self, _state: &S, actions: &[A]) -> Vec<f64> {
+        values_of(&self.values, actions.len())
+    }
+}
+
+/// What `ThompsonSampling` believes about rewards before trying an action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prior {
+    /// rewards between 0 and 1, e.g. clicks. Starts uniform
+    Beta,
+    /// rewards with standard deviation 1. Starts at a standard normal
+    Gaussian,
+}
+
+/// Keeps a distribution of what each action's mean reward could be, and picks the best of a sample from each
+pub struct ThompsonSampling {
+    prior: Prior,
+    counts: Vec<usize>,
+    /// sum of rewards for `Prior::Gaussian`, or successes for `Prior::Beta`
+    sums: Vec<f64>,
+}
+
+impl ThompsonSampling {
+    pub fn new(prior: Prior) -> Self {
+        Self {
+            prior,
+            counts: vec![],
+            sums: vec![],
+        }
+    }
+
+    /// Mean of each action's distribution
+    pub fn values(&self) -> Vec<f64> {
+        self.counts
+            .iter()
+            .zip(&self.sums)
+            .map(|(&n, sum)| match self.prior {
+                Prior::Beta => (sum + 1.) / (n as f64 + 2.),
+                Prior::Gaussian => sum / (n as f64 + 1.),
+            })
+            .collect()
+    }
+
+    fn sample(&self, index: usize) -> f64 {
+        let n = self.counts[index] as f64;
+        let sum = self.sums[index];
+        match self.prior {
+            Prior::Beta => beta(sum + 1., n - sum + 1.),
+            Prior::Gaussian => normal(sum / (n + 1.), 1. / (n + 1.).sqrt()),
+        }
+    }
+}
+
+impl<S, A: Clone> Agent<S, A> for ThompsonSampling {
+    fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
+        let actions = env.all_actions();
+        if actions.is_empty() {
+            return true;
+        }
+        resize(&mut self.counts, actions.len());
+        resize(&mut self.sums, actions.len());
+        let samples: Vec<f64> = (0..actions.len()).map(|i| self.sample(i)).collect();
+        let index = best_index(&samples);
+
+        let reward = env.take_action_get_reward(&actions[index]);
+        self.counts[index] += 1;
+        self.sums[index] += match self.prior {
+            //rewards between 0 and 1 count as a success with that chance
+            Prior::Beta => (fastrand::f64() < reward) as u8 as f64,
+            Prior::Gaussian => reward,
+        };
+
+        env.should_stop(progress.epoch_step)
+    }
+
+    fn pick_action(&mut self, actions: &[A], best: Option<A>, _progress: Progress) -> A {
+        best_or_random(actions, best)
+    }
+}
+
+impl<S, A> Policy<S, A> for ThompsonSampling {
+    fn action_values(&self, _state: &S, actions: &[A]) -> Vec<f64> {
+        values_of(&self.values(), actions.len())
+    }
+}
+
+/// Contextual bandit agent which learns a linear model of reward from `Environment::observation` for each action,
+/// and picks the best upper confidence bound.
+/// With no observation e.g. `Bandit`, the only feature is a bias of 1, so it learns the mean of each action like `Ucb1`
+pub struct LinUcb {
+    alpha: f64,
+    /// inverse of each action's covariance matrix, row by row
+    inverses: Vec<Vec<f64>>,
+    /// sum of features multiplied by reward, for each action
+    targets: Vec<Vec<f64>>,
+}
+
+impl LinUcb {
+    /// `alpha` is how much to explore e.g. 1
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            inverses: vec![],
+            targets: vec![],
+        }
+    }
+
+    /// Estimated reward of each action for `features`, and how uncertain it is
+    fn estimates(&self, features: &[f64]) -> Vec<(f64, f64)> {
+        self.inverses
+            .iter()
+            .zip(&self.targets)
+            .map(|(inverse, target)| {
+                let scaled = multiply(inverse, features);
+                let weights = multiply(inverse, target);
+                (dot(&weights, features), dot(&scaled, features).sqrt())
+            })
+            .collect()
+    }
+
+    /// Starts every action with the identity matrix, once we know how many features there are
+    fn resize(&mut self, actions: usize, features: usize) {
+        if self.targets.first().map(Vec::len) != Some(features) {
+            self.inverses.clear();
+            self.targets.clear();
+        }
+        let identity: Vec<f64> = (0..features * features)
+            .map(|i| (i % (features + 1) == 0) as u8 as f64)
+            .collect();
+        self.inverses.resize(actions, identity);
+        self.targets.resize(actions, vec![0.; features]);
+    }
+
+    /// Sherman-Morrison update of the inverse, so we never invert a matrix
+    fn learn(&mut self, index: usize, features: &[f64], reward: f64) {
+        let inverse = &mut self.inverses[index];
+        let scaled = multiply(inverse, features);
+        let denominator = 1. + dot(&scaled, features);
+        let size = features.len();
+        for row in 0..size {
+            for col in 0..size {
+                inverse[row * size + col] -= scaled[row] * scaled[col] / denominator;
+            }
+        }
+        for (target, x) in self.targets[index].iter_mut().zip(features) {
+            *target += reward * x;
+        }
+    }
+}
+
+impl<S, A: Clone> Agent<S, A> for LinUcb {
+    fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
+        let actions = env.all_actions();
+        if actions.is_empty() {
+            return true;
+        }
+        let features = features(env.observation().into_iter().map(f64::from).collect());
+        self.resize(actions.len(), features.len());
+        let bounds: Vec<f64> = self
+            .estimates(&features)
+            .into_iter()
+            .map(|(mean, uncertainty)| mean + self.alpha * uncertainty)
+            .collect();
+        let index = best_index(&bounds);
+
+        let reward = env.take_action_get_reward(&actions[index]);
+        self.learn(index, &features, reward);
+
+        env.should_stop(progress.epoch_step)
+    }
+
+    fn pick_action(&mut self, actions: &[A], best: Option<A>, _progress: Progress) -> A {
+        best_or_random(actions, best)
+    }
+}
+
+/// The state is the features e.g. from `ContextualBandit`
+impl<A> Policy<Vec<f64>, A> for LinUcb {
+    fn action_values(&self, state: &Vec<f64>, actions: &[A]) -> Vec<f64> {
+        let state = features(state.clone());
+        let means: Vec<f64> = if self.targets.first().map(Vec::len) == Some(state.len()) {
+            self.estimates(&state).into_iter().map(|x| x.0).collect()
+        } else {
+            vec![]
+        };
+        values_of(&means, actions.len())
+    }
+}
+
+/// Just a bias of 1 when there are no features, so every action gets 1 weight for its mean
+fn features(observation: Vec<f64>) -> Vec<f64> {
+    if observation.is_empty() {
+        vec![1.]
+    } else {
+        observation
+    }
+}
+
+/// No features e.g. `Bandit`, so the values are the mean of each action
+impl<A> Policy<(), A> for LinUcb {
+    fn action_values(&self, _state: &(), actions: &[A]) -> Vec<f64> {
+        Policy::<Vec<f64>, A>::action_values(self, &vec![], actions)
+    }
+}
+
+/// Grows with 0s for new actions
+fn resize<T: Default + Clone>(values: &mut Vec<T>, len: usize) {
+    if values.len() < len {
+        values.resize(len, T::default());
+    }
+}
+
+/// `values` for the first `len` actions, with 0 for actions not tried yet
+fn values_of(values: &[f64], len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|i| values.get(i).copied().unwrap_or_default())
+        .collect()
+}
+
+/// Index of the highest value, picking at random between ties so untried actions are not always in order
+fn best_index(values: &[f64]) -> usize {
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    let best: Vec<usize> = (0..values.len()).filter(|&i| values[i] == max).collect();
+    best.get(fastrand::usize(0..best.len().max(1)))
+        .copied()
+        .unwrap_or(0)
+}
+
+fn best_or_random<A: Clone>(actions: &[A], best: Option<A>) -> A {
+    best.unwrap_or_else(|| actions[fastrand::usize(0..actions.len())].clone())
+}
+
+/// Square `matrix`, row by row, multiplied by `vector`
+fn multiply(matrix: &[f64], vector: &[f64]) -> Vec<f64> {
+    matrix
+        .chunks(vector.len())
+        .map(|row| dot(row, vector))
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{envs::bandit::Bandit, runner::Runner};
+
+    const PULLS: usize = 2000;
+    /// expected reward lost by each arm, against always pulling the last
+    const GAPS: [f64; 3] = [0.6, 0.3, 0.];
+
+    /// Runs `agent` on 3 bernoulli arms where the last is best, checking it ends up picking that arm
+    /// and losing much less than the 0.3 a pull of picking at random
+    fn converges<AG: Agent<(), usize> + Policy<(), usize>>(agent: AG) -> (AG, Vec<f64>) {
+        fastrand::seed(3);
+        let (agent, metrics) =
+            Runner::run_with_metrics(agent, Bandit::bernoulli(&[0.2, 0.5, 0.8]), PULLS);
+        assert_eq!(agent.act(&(), &[0, 1, 2]), 2);
+        let regrets = metrics.regrets;
+        assert_eq!(regrets.len(), PULLS);
+        assert!(regrets.windows(2).all(|x| x[0] <= x[1]));
+        assert!(
+            regrets[PULLS - 1] < 0.05 * PULLS as f64,
+            "{}",
+            regrets[PULLS - 1]
+        );
+        (agent, regrets)
+    }
+
+    /// Regret from how often each arm was pulled
+    fn regret_of(counts: &[usize]) -> f64 {
+        counts
+            .iter()
+            .zip(GAPS)
+            .map(|(&n, gap)| n as f64 * gap)
+            .sum()
+    }
+
+    #[test]
+    fn ucb1_converges() {
+        let (agent, regrets) = converges(Ucb1::new(2f64.sqrt()));
+        assert_eq!(agent.counts.iter().sum::<usize>(), PULLS);
+        assert!((regrets[PULLS - 1] - regret_of(&agent.counts)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn thompson_sampling_converges() {
+        let (agent, regrets) = converges(ThompsonSampling::new(Prior::Beta));
+        assert!((regrets[PULLS - 1] - regret_of(&agent.counts)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn linucb_uses_a_bias_without_features() {
+        let (agent, _) = converges(LinUcb::new(1.));
+        assert!(agent.targets.iter().all(|target| target.len() == 1));
+        let means: Vec<f64> = agent.action_values(&(), &[0, 1, 2]);
+        assert!((means[2] - 0.8).abs() < 0.05, "{means:?}");
+    }
+}
@@ -1,8 +1,22 @@
-use crate::{environment::Environment, progress::Progress, strategy::ExploreStrategy};
+use crate::{
+    environment::{
+        vecenv::{VecEnv, VecStep},
+        Environment,
+    },
+    progress::Progress,
+    strategy::ExploreStrategy,
+};
 use core::hash::Hash;
 use std::collections::VecDeque;
 
-use super::{nnbackend::NNBackend, Agent};
+use super::{nnbackend::NNBackend, Agent, JointAgent, ParallelAgent, Policy, Transition, VecAgent};
+#[cfg(feature = "serde")]
+use crate::{
+    agent::Checkpoint,
+    persist::{self, Format, PersistError},
+};
+#[cfg(feature = "serde")]
+use std::io::{Read, Write};
 
 /// Here we use neural network to predict actions
 /// We use display (image) as input
@@ -14,10 +28,11 @@ pub struct DeepQLearning<A, NB> {
     nn: NB,        //make predictions
     nn_target: NB, //used to calculate target Q, updated periodically
     config: DeepQLearningConfig,
-    strategy: Box<(dyn ExploreStrategy<A> + Send)>,
+    strategy: Box<dyn ExploreStrategy<A> + Send>,
     history: VecDeque<Replay>,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Replay {
     state: Vec<f32>,
     action_index: usize,
@@ -67,7 +82,7 @@ impl<A, NB: NNBackend> DeepQLearning<A, NB> {
     /// nnf is the function used to create the neural network which conforms to traint NNBackend
     /// It passes in the `shape` of all layers including input, hidden and output, and `learning_rate` as per config
     /// `hidden_sizes` is a list of sizes of hidden layers
-    /// We pass in the environment so that we can auto calculated the input size (based on image) and output sizes (based on actions)
+    /// We pass in the environment so that we can auto calculated the input size (based on `Environment::observation`) and output sizes (based on actions)
     pub fn new<S, F: Fn(&Vec<usize>, f32) -> NB>(
         nnf: F,
         hidden_sizes: &[usize],
@@ -76,7 +91,7 @@ impl<A, NB: NNBackend> DeepQLearning<A, NB> {
         env: &mut dyn Environment<S, A>,
     ) -> Self {
         env.reset(1);
-        let input_state = env.get_image().data().len();
+        let input_state = env.observation().len();
         let action_count = env.all_actions().len();
 
         let shape: Vec<usize> = vec![vec![input_state], hidden_sizes.to_vec(), vec![action_count]]
@@ -118,29 +133,34 @@ impl<A, NB: NNBackend> DeepQLearning<A, NB> {
         let mut set: Vec<&Replay> = vec![];
         selected.iter().for_each(|&x| set.push(&self.history[x]));
 
+        //batch network calls: we use nn_target to predict next, and nn for current
+        let next_states: Vec<Vec<f32>> = set
+            .iter()
+            .filter(|x| !x.done)
+            .map(|x| x.next_state.clone())
+            .collect();
+        let mut next_outputs = self.nn_target.forward_batch(&next_states).into_iter();
+        let states: Vec<Vec<f32>> = set.iter().map(|x| x.state.clone()).collect();
+        let predicted = self.nn.forward_batch(&states);
+
         let mut batch_inputs = vec![];
         let mut batch_outputs = vec![];
-        for item in set {
+        for (item, mut predicted_next) in set.into_iter().zip(predicted) {
             let input = &item.state;
 
             //next reward
-            let next_reward;
-            if item.done {
-                next_reward = 0.; //there is no next if we are done
+            let next_reward = if item.done {
+                0. //there is no next if we are done
             } else {
-                //we use nn_target to predict
-                let mut outputs: Vec<f32> = self.nn_target.forward(&item.next_state);
-                //let mut outputs: Vec<f32> = self.nn.forward(&item.next_state);
-                outputs.sort_by(|a, b| a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal));
-                next_reward = outputs.into_iter().last().unwrap_or_default();
-            }
+                let mut outputs: Vec<f32> = next_outputs.next().unwrap_or_default();
+                outputs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                outputs.into_iter().last().unwrap_or_default()
+            };
 
             //target reward
             let target_reward = item.reward + self.config.discount * next_reward;
 
             //predicted next - here we use the same for the others, but change the target action to be the best next
-            let mut predicted_next = self.nn.forward(&input);
-
             let old_reward = predicted_next[item.action_index];
             let diff = target_reward - old_reward;
             let change = diff * self.config.q_learning_rate;
@@ -159,21 +179,88 @@ impl<A, NB: NNBackend> DeepQLearning<A, NB> {
         A: Clone + Hash + Eq,
     {
         let current_state = self.get_state(env);
-        let mut output: Vec<(usize, f32)> = self
-            .nn
-            .forward(&current_state)
-            .into_iter()
-            .enumerate()
-            .collect();
-        output.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-        let best_index = output.last().unwrap().0;
+        let best_index = best_index(self.nn.forward(&current_state));
 
-        let best = env.all_actions()[best_index].clone();
-        best
+        env.all_actions()[best_index].clone()
     }
 
-    fn get_state<S>(&self, env: &mut dyn Environment<S, A>) -> Vec<f32> {
-        env.get_image().data().iter().map(|x| *x as f32).collect()
+    fn remember(&mut self, replay: Replay) {
+        self.history.push_back(replay);
+        if self.history.len() > self.config.history_size {
+            self.history.pop_front();
+        }
+    }
+
+    /// Trains and updates the target network for every `train_steps` and `copy_nn_steps`
+    /// passed going from `previous` to `current` cumulative steps
+    fn train_between(&mut self, previous: usize, current: usize) {
+        //0 means never
+        let passed = |every: usize| {
+            current.checked_div(every).unwrap_or(0) - previous.checked_div(every).unwrap_or(0)
+        };
+        for _ in 0..passed(self.config.train_steps) {
+            self.train_nn();
+        }
+        if passed(self.config.copy_nn_steps) > 0 {
+            self.nn_target.update_from(&self.nn);
+        }
+    }
+
+    /// The input to the neural network, from `Environment::observation`
+    pub fn get_state<S>(&self, env: &mut dyn Environment<S, A>) -> Vec<f32> {
+        env.observation()
+    }
+}
+
+/// What gets saved to file. `R` is borrowed when saving and owned when loading
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeepQLearningFile<R> {
+    nn: Vec<f32>,
+    /// e.g. Adam moments, so training continues the same
+    nn_optimizer: Vec<f64>,
+    nn_target: Vec<f32>,
+    strategy: Vec<f64>,
+    history: Vec<R>,
+}
+
+/// Saves both networks, the optimizer state of the one being trained, strategy state and the replay history.
+/// Loading fails if the networks are a different size
+#[cfg(feature = "serde")]
+impl<A, NB: NNBackend> Checkpoint for DeepQLearning<A, NB> {
+    fn save_checkpoint(&self, writer: &mut dyn Write) -> Result<(), PersistError> {
+        let file = DeepQLearningFile {
+            nn: self.nn.weights(),
+            nn_optimizer: self.nn.optimizer_snapshot(),
+            nn_target: self.nn_target.weights(),
+            strategy: self.strategy.snapshot(),
+            history: self.history.iter().collect(),
+        };
+        persist::to_writer(writer, Format::Binary, "deepqlearning", &file)
+    }
+
+    fn load_checkpoint(&mut self, reader: &mut dyn Read) -> Result<(), PersistError> {
+        let file: DeepQLearningFile<Replay> =
+            persist::from_reader(reader, Format::Binary, "deepqlearning")?;
+        let expected = self.nn.weights().len();
+        for weights in [&file.nn, &file.nn_target] {
+            if weights.len() != expected {
+                return Err(PersistError::Format(format!(
+                    "network has {expected} weights, checkpoint has {}",
+                    weights.len()
+                )));
+            }
+        }
+        self.nn.set_weights(&file.nn);
+        self.nn
+            .restore_optimizer(&file.nn_optimizer)
+            .map_err(PersistError::Format)?;
+        self.nn_target.set_weights(&file.nn_target);
+        self.strategy
+            .restore(&file.strategy)
+            .map_err(PersistError::Format)?;
+        self.history = file.history.into();
+        Ok(())
     }
 }
 
@@ -191,6 +278,10 @@ where
         self.strategy.pick_action(actions, best, progress)
     }
 
+    fn exploration(&self) -> Option<f64> {
+        self.strategy.exploration()
+    }
+
     /// Trains every N number of steps
     fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
         let best = self.get_best_action(env);
@@ -212,26 +303,218 @@ where
         let done = env.should_stop(progress.epoch_step);
         let next_state = self.get_state(env);
         //now save to history
-        self.history.push_back(Replay {
+        self.remember(Replay {
             state,
             action_index,
             next_state,
             reward,
             done,
         });
+        self.train_between(progress.cumulative_steps - 1, progress.cumulative_steps);
 
-        if self.history.len() > self.config.history_size {
-            self.history.pop_front();
-        }
+        done
+    }
+}
 
-        if progress.cumulative_steps % self.config.train_steps == 0 {
-            self.train_nn();
+impl<S, A, NB> VecAgent<S, A> for DeepQLearning<A, NB>
+where
+    A: Clone + Hash + Eq,
+    NB: NNBackend,
+{
+    /// Picks actions for all environments with one network call,
+    /// and trains as often as if the steps were taken one at a time
+    fn step_vec<E: Environment<S, A>>(
+        &mut self,
+        progress: Progress,
+        envs: &mut VecEnv<E>,
+    ) -> VecStep {
+        let states = envs.observations();
+        let outputs = self.nn.forward_batch(&states);
+        let mut actions = Vec::with_capacity(envs.len());
+        let mut action_indexes = Vec::with_capacity(envs.len());
+        for (env, output) in envs.envs().iter().zip(outputs) {
+            let all_actions = env.all_actions();
+            let best = all_actions[best_index(output)].clone();
+            let action = self
+                .strategy
+                .pick_action(&all_actions, Some(best), progress);
+            action_indexes.push(all_actions.iter().position(|x| x == &action).unwrap());
+            actions.push(action);
         }
 
-        if progress.cumulative_steps % self.config.copy_nn_steps == 0 {
-            self.nn_target.update_from(&self.nn);
+        let step = envs.step(&actions);
+        for (i, state) in states.into_iter().enumerate() {
+            self.remember(Replay {
+                state,
+                action_index: action_indexes[i],
+                next_state: step.next_observations[i].clone(),
+                reward: step.rewards[i] as f32,
+                done: step.dones[i],
+            });
         }
+        self.train_between(
+            progress.cumulative_steps - envs.len(),
+            progress.cumulative_steps,
+        );
+        step
+    }
+}
 
-        done
+/// Workers pick actions with a copy of the network, which is updated every `ParallelConfig::sync_steps`
+impl<S, A, NB> ParallelAgent<S, A> for DeepQLearning<A, NB>
+where
+    A: Clone + Hash + Eq,
+    NB: NNBackend + Clone + Send + Sync,
+{
+    type Observation = Vec<f32>;
+    type Actor = NB;
+
+    fn observe(env: &mut dyn Environment<S, A>) -> Vec<f32> {
+        env.observation()
+    }
+
+    fn actor(&self) -> NB {
+        self.nn.clone()
+    }
+
+    fn best_action(actor: &NB, observation: &Vec<f32>, actions: &[A]) -> Option<A> {
+        Some(actions[best_index(actor.forward(observation))].clone())
+    }
+
+    /// Trains every `train_steps` transitions learned
+    fn learn(&mut self, transition: Transition<Vec<f32>, A>, progress: Progress) {
+        self.remember(Replay {
+            state: transition.state,
+            action_index: transition.action_index,
+            next_state: transition.next_state,
+            reward: transition.reward as f32,
+            done: transition.done,
+        });
+        self.train_between(progress.cumulative_steps - 1, progress.cumulative_steps);
+    }
+}
+
+impl<S, A, NB> JointAgent<S, A> for DeepQLearning<A, NB>
+where
+    A: Clone + Hash + Eq,
+    NB: NNBackend + Clone + Send + Sync,
+{
+    fn best(&self, observation: &Vec<f32>, actions: &[A]) -> Option<A> {
+        Some(actions[best_index(self.nn.forward(observation))].clone())
+    }
+}
+
+/// Index of the highest output, taking the last if there are ties
+fn best_index(output: Vec<f32>) -> usize {
+    let mut output: Vec<(usize, f32)> = output.into_iter().enumerate().collect();
+    output.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    output.last().unwrap().0
+}
+
+/// State is the image as used in training (see `DeepQLearning::get_state`),
+/// and `actions` must be ALL actions in the same order as `Environment::all_actions`
+impl<A, NB: NNBackend> Policy<Vec<f32>, A> for DeepQLearning<A, NB> {
+    fn action_values(&self, state: &Vec<f32>, actions: &[A]) -> Vec<f64> {
+        let output = self.nn.forward(state);
+        assert_eq!(
+            output.len(),
+            actions.len(),
+            "network outputs do not match actions"
+        );
+        output.into_iter().map(|x| x as f64).collect()
+    }
+}
+
+#[cfg(all(test, feature = "serde", feature = "mlp"))]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::nnbackend::mlp::{Mlp, Optimizer},
+        envs::cartpole::{CartPole, CartPoleAction},
+        runner::{CheckpointConfig, Runner},
+        strategy::decliningrandom::DecliningRandom,
+    };
+
+    fn agent(hidden: usize) -> DeepQLearning<CartPoleAction, Mlp> {
+        let nn =
+            |shape: &Vec<usize>, lr: f32| Mlp::new(shape, lr).with_optimizer(Optimizer::adam());
+        let config = DeepQLearningConfig {
+            replay_size: 8,
+            nn_batch_size: 8,
+            train_steps: 1,
+            copy_nn_steps: 20,
+            ..Default::default()
+        };
+        let strategy = DecliningRandom::new(20, 0.1);
+        DeepQLearning::new(nn, &[hidden], config, strategy, &mut CartPole::default())
+    }
+
+    #[test]
+    fn resumed_adam_matches_uninterrupted_run() {
+        let epochs = 20;
+        let dir = std::env::temp_dir().join(format!("learnwell-dqn-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = |name: &str| CheckpointConfig {
+            path: dir.join(name),
+            every: 5,
+        };
+
+        fastrand::seed(3);
+        let whole =
+            Runner::run_with_checkpoints(agent(8), CartPole::default(), epochs, &config("whole"))
+                .unwrap();
+
+        fastrand::seed(3);
+        let interrupted = config("interrupted");
+        Runner::run_with_checkpoints(agent(8), CartPole::default(), 12, &interrupted).unwrap();
+        fastrand::seed(99);
+        let resumed = Runner::resume(agent(8), CartPole::default(), epochs, &interrupted).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(whole.nn.weights(), resumed.nn.weights());
+        assert_eq!(
+            whole.nn.optimizer_snapshot(),
+            resumed.nn.optimizer_snapshot()
+        );
+    }
+
+    #[test]
+    fn checkpoint_of_different_network_is_an_error() {
+        let mut bytes = vec![];
+        agent(8).save_checkpoint(&mut bytes).unwrap();
+        let result = agent(16).load_checkpoint(&mut bytes.as_slice());
+        assert!(matches!(result, Err(PersistError::Format(_))));
+    }
+
+    #[test]
+    fn checkpoint_round_trip() {
+        fastrand::seed(5);
+        let (trained, _) = Runner::run_with_metrics(agent(8), CartPole::default(), 3);
+        let mut bytes = vec![];
+        trained.save_checkpoint(&mut bytes).unwrap();
+
+        let mut loaded = agent(8);
+        loaded.load_checkpoint(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.nn.weights(), trained.nn.weights());
+        assert_eq!(loaded.nn_target.weights(), trained.nn_target.weights());
+        assert_eq!(
+            loaded.nn.optimizer_snapshot(),
+            trained.nn.optimizer_snapshot()
+        );
+        assert_eq!(loaded.strategy.snapshot(), trained.strategy.snapshot());
+        assert_eq!(loaded.history.len(), trained.history.len());
+        assert!(loaded
+            .history
+            .iter()
+            .zip(&trained.history)
+            .all(|(a, b)| a.state == b.state && a.action_index == b.action_index));
+    }
+
+    #[test]
+    fn checkpoint_of_other_kind_is_an_error() {
+        let mut bytes = vec![];
+        persist::to_writer(&mut bytes, Format::Binary, "qlearning", &1u32).unwrap();
+        let result = agent(8).load_checkpoint(&mut bytes.as_slice());
+        assert!(matches!(result, Err(PersistError::Kind { .. })));
     }
 }
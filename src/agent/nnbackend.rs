@@ -1,5 +1,39 @@
+#[cfg(feature = "mlp")]
+pub mod mlp;
+
 pub trait NNBackend {
-    fn update_from(&mut self, other: &Self);
-    fn forward(&mut self, input: &[f32]) -> Vec<f32>;
+    fn forward(&self, input: &[f32]) -> Vec<f32>;
+    /// Outputs for several inputs at once. Override if the network can do this faster than one at a time
+    fn forward_batch(&self, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        inputs.iter().map(|x| self.forward(x)).collect()
+    }
     fn fit(&mut self, inputs: &[Vec<f32>], outputs: &[Vec<f32>], batch_size: usize);
+    /// All weights and biases as a flat list, in the same order `set_weights` expects them
+    fn weights(&self) -> Vec<f32>;
+    fn set_weights(&mut self, weights: &[f32]);
+
+    /// Optimizer state which is not part of `weights` e.g. Adam moments, so training can continue exactly where it was
+    fn optimizer_snapshot(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    /// Restore values saved with `optimizer_snapshot`. Fails if they are from a different optimizer or shape
+    fn restore_optimizer(&mut self, snapshot: &[f64]) -> Result<(), String> {
+        if snapshot.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected no optimizer values, found {}",
+                snapshot.len()
+            ))
+        }
+    }
+
+    /// Copy weights from `other`, which has the same shape
+    fn update_from(&mut self, other: &Self)
+    where
+        Self: Sized,
+    {
+        self.set_weights(&other.weights());
+    }
 }
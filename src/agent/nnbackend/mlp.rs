@@ -0,0 +1,612 @@
+use super::NNBackend;
+
+/// Simple multilayer perceptron written in pure rust, so no other libraries are required.
+/// Create using `Mlp::new` which has the same signature expected by `DeepQLearning::new`,
+/// and customise with the `with_` methods e.g.
+/// ```ignore
+/// let nnf = |shape: &Vec<usize>, lr: f32| {
+///     Mlp::new(shape, lr)
+///         .with_hidden(Activation::Relu)
+///         .with_initialization(Initialization::Xavier)
+///         .with_loss(Loss::Huber(1.))
+///         .with_optimizer(Optimizer::adam())
+///         .with_gradient_clip(10.)
+/// };
+/// ```
+/// Defaults are sigmoid hidden layers, linear output, mean squared error, SGD and weights between -1 and 1
+#[derive(Clone)]
+pub struct Mlp {
+    layers: Vec<Layer>,
+    learning_rate: f32,
+    hidden: Activation,
+    output: Activation,
+    loss: Loss,
+    optimizer: Optimizer,
+    /// maximum norm of all gradients together
+    gradient_clip: Option<f32>,
+    /// number of updates so far, used by Adam
+    updates: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    Linear,
+    Sigmoid,
+    Tanh,
+    Relu,
+    /// Relu with given slope for negative values e.g. 0.01
+    LeakyRelu(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Initialization {
+    /// weights and biases random between -x and x
+    Uniform(f32),
+    /// weights scaled by layer sizes and zero biases, better for relu or large layers
+    Xavier,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Loss {
+    /// Mean squared error
+    Mse,
+    /// Squared error up to `delta`, then linear, so large errors do not cause large updates
+    Huber(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Optimizer {
+    /// Stochastic gradient descent
+    Sgd,
+    Adam {
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+    },
+}
+
+impl Optimizer {
+    /// Adam with the usual defaults
+    pub fn adam() -> Self {
+        Optimizer::Adam {
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+/// Fully connected layer.
+/// `weights` has `outputs` rows of `inputs`
+#[derive(Clone)]
+struct Layer {
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+    //Adam moments, same layout as weights and biases
+    weight_moments: Vec<(f32, f32)>,
+    bias_moments: Vec<(f32, f32)>,
+}
+
+/// Gradients for each layer, in the same layout as the layers
+struct Gradients {
+    weights: Vec<Vec<f32>>,
+    biases: Vec<Vec<f32>>,
+}
+
+impl Activation {
+    fn activate(&self, z: f32) -> f32 {
+        match self {
+            Activation::Linear => z,
+            Activation::Sigmoid => 1. / (1. + (-z).exp()),
+            Activation::Tanh => z.tanh(),
+            Activation::Relu => z.max(0.),
+            Activation::LeakyRelu(slope) => {
+                if z > 0. {
+                    z
+                } else {
+                    z * slope
+                }
+            }
+        }
+    }
+
+    /// derivative based on the activated value `a`
+    fn derivative(&self, a: f32) -> f32 {
+        match self {
+            Activation::Linear => 1.,
+            Activation::Sigmoid => a * (1. - a),
+            Activation::Tanh => 1. - a * a,
+            Activation::Relu => {
+                if a > 0. {
+                    1.
+                } else {
+                    0.
+                }
+            }
+            Activation::LeakyRelu(slope) => {
+                if a > 0. {
+                    1.
+                } else {
+                    *slope
+                }
+            }
+        }
+    }
+}
+
+impl Loss {
+    /// gradient of loss with respect to output
+    fn derivative(&self, output: f32, target: f32) -> f32 {
+        let diff = output - target;
+        match self {
+            Loss::Mse => diff,
+            Loss::Huber(delta) => diff.clamp(-delta, *delta),
+        }
+    }
+}
+
+impl Layer {
+    fn new(inputs: usize, outputs: usize, initialization: Initialization) -> Self {
+        let random = |limit: f32| (fastrand::f32() * 2. - 1.) * limit;
+        let (weight_limit, bias_limit) = match initialization {
+            Initialization::Uniform(x) => (x, x),
+            Initialization::Xavier => ((6. / (inputs + outputs) as f32).sqrt(), 0.),
+        };
+        Self {
+            inputs,
+            outputs,
+            weights: (0..inputs * outputs)
+                .map(|_| random(weight_limit))
+                .collect(),
+            biases: (0..outputs).map(|_| random(bias_limit)).collect(),
+            weight_moments: vec![(0., 0.); inputs * outputs],
+            bias_moments: vec![(0., 0.); outputs],
+        }
+    }
+
+    fn forward(&self, input: &[f32], activation: Activation) -> Vec<f32> {
+        (0..self.outputs)
+            .map(|o| {
+                let row = &self.weights[o * self.inputs..(o + 1) * self.inputs];
+                let z: f32 =
+                    row.iter().zip(input).map(|(w, i)| w * i).sum::<f32>() + self.biases[o];
+                activation.activate(z)
+            })
+            .collect()
+    }
+
+    /// Same as `forward` for `count` inputs stored one after another in `inputs`.
+    /// Each row of weights is used for the whole batch before moving to the next, instead of once per input
+    fn forward_batch(&self, inputs: &[f32], count: usize, activation: Activation) -> Vec<f32> {
+        let mut output = vec![0.; count * self.outputs];
+        for o in 0..self.outputs {
+            let row = &self.weights[o * self.inputs..(o + 1) * self.inputs];
+            for (b, input) in inputs.chunks_exact(self.inputs).enumerate() {
+                let z: f32 =
+                    row.iter().zip(input).map(|(w, i)| w * i).sum::<f32>() + self.biases[o];
+                output[b * self.outputs + o] = activation.activate(z);
+            }
+        }
+        output
+    }
+}
+
+impl Mlp {
+    /// `shape` includes input and output sizes e.g. [4, 32, 2]
+    /// Panics if there are fewer than 2 layers
+    pub fn new(shape: &[usize], learning_rate: f32) -> Self {
+        assert!(shape.len() >= 2, "need at least input and output layers");
+        Self {
+            layers: Self::create_layers(shape, Initialization::Uniform(1.)),
+            learning_rate,
+            hidden: Activation::Sigmoid,
+            output: Activation::Linear,
+            loss: Loss::Mse,
+            optimizer: Optimizer::Sgd,
+            gradient_clip: None,
+            updates: 0,
+        }
+    }
+
+    fn create_layers(shape: &[usize], initialization: Initialization) -> Vec<Layer> {
+        shape
+            .windows(2)
+            .map(|x| Layer::new(x[0], x[1], initialization))
+            .collect()
+    }
+
+    /// Recreates weights using `initialization`
+    pub fn with_initialization(mut self, initialization: Initialization) -> Self {
+        let mut shape: Vec<usize> = self.layers.iter().map(|l| l.inputs).collect();
+        shape.extend(self.layers.last().map(|l| l.outputs));
+        self.layers = Self::create_layers(&shape, initialization);
+        self
+    }
+
+    pub fn with_hidden(mut self, activation: Activation) -> Self {
+        self.hidden = activation;
+        self
+    }
+
+    pub fn with_output(mut self, activation: Activation) -> Self {
+        self.output = activation;
+        self
+    }
+
+    pub fn with_loss(mut self, loss: Loss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    pub fn with_optimizer(mut self, optimizer: Optimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    /// Scale gradients down so their combined norm is at most `max_norm`
+    pub fn with_gradient_clip(mut self, max_norm: f32) -> Self {
+        self.gradient_clip = Some(max_norm);
+        self
+    }
+
+    fn activation(&self, layer: usize) -> Activation {
+        if layer == self.layers.len() - 1 {
+            self.output
+        } else {
+            self.hidden
+        }
+    }
+
+    /// values of every layer including input
+    fn forward_all(&self, input: &[f32]) -> Vec<Vec<f32>> {
+        let mut values = vec![input.to_vec()];
+        for (l, layer) in self.layers.iter().enumerate() {
+            let next = layer.forward(values.last().unwrap(), self.activation(l));
+            values.push(next);
+        }
+        values
+    }
+
+    /// average gradients over the batch
+    fn gradients(&self, inputs: &[Vec<f32>], targets: &[Vec<f32>]) -> Gradients {
+        let mut grads = Gradients {
+            weights: self
+                .layers
+                .iter()
+                .map(|l| vec![0.; l.weights.len()])
+                .collect(),
+            biases: self
+                .layers
+                .iter()
+                .map(|l| vec![0.; l.biases.len()])
+                .collect(),
+        };
+
+        for (input, target) in inputs.iter().zip(targets) {
+            let values = self.forward_all(input);
+            //dE/dA for the output layer
+            let mut error: Vec<f32> = values
+                .last()
+                .unwrap()
+                .iter()
+                .zip(target)
+                .map(|(o, t)| self.loss.derivative(*o, *t))
+                .collect();
+
+            for l in (0..self.layers.len()).rev() {
+                let layer = &self.layers[l];
+                let activation = self.activation(l);
+                //dE/dZ = dE/dA * dA/dZ
+                let dz: Vec<f32> = error
+                    .iter()
+                    .zip(&values[l + 1])
+                    .map(|(e, a)| e * activation.derivative(*a))
+                    .collect();
+
+                let input = &values[l];
+                for (o, d) in dz.iter().enumerate() {
+                    grads.biases[l][o] += d;
+                    let row = &mut grads.weights[l][o * layer.inputs..(o + 1) * layer.inputs];
+                    row.iter_mut().zip(input).for_each(|(g, i)| *g += d * i);
+                }
+
+                //pass error down to previous layer: dE/dA = dE/dZ * W
+                error = (0..layer.inputs)
+                    .map(|i| {
+                        dz.iter()
+                            .enumerate()
+                            .map(|(o, d)| d * layer.weights[o * layer.inputs + i])
+                            .sum()
+                    })
+                    .collect();
+            }
+        }
+
+        let count = inputs.len() as f32;
+        grads
+            .weights
+            .iter_mut()
+            .chain(grads.biases.iter_mut())
+            .flatten()
+            .for_each(|g| *g /= count);
+
+        if let Some(max_norm) = self.gradient_clip {
+            let norm = grads
+                .weights
+                .iter()
+                .chain(grads.biases.iter())
+                .flatten()
+                .map(|g| g * g)
+                .sum::<f32>()
+                .sqrt();
+            if norm > max_norm {
+                let scale = max_norm / norm;
+                grads
+                    .weights
+                    .iter_mut()
+                    .chain(grads.biases.iter_mut())
+                    .flatten()
+                    .for_each(|g| *g *= scale);
+            }
+        }
+        grads
+    }
+
+    fn apply(&mut self, grads: &Gradients) {
+        self.updates += 1;
+        let lr = self.learning_rate;
+        let optimizer = self.optimizer;
+        let updates = self.updates;
+        let step = |param: &mut f32, grad: f32, moment: &mut (f32, f32)| match optimizer {
+            Optimizer::Sgd => *param -= lr * grad,
+            Optimizer::Adam {
+                beta1,
+                beta2,
+                epsilon,
+            } => {
+                moment.0 = beta1 * moment.0 + (1. - beta1) * grad;
+                moment.1 = beta2 * moment.1 + (1. - beta2) * grad * grad;
+                let m = moment.0 / (1. - beta1.powi(updates));
+                let v = moment.1 / (1. - beta2.powi(updates));
+                *param -= lr * m / (v.sqrt() + epsilon);
+            }
+        };
+
+        for (l, layer) in self.layers.iter_mut().enumerate() {
+            for ((w, g), m) in layer
+                .weights
+                .iter_mut()
+                .zip(&grads.weights[l])
+                .zip(layer.weight_moments.iter_mut())
+            {
+                step(w, *g, m);
+            }
+            for ((b, g), m) in layer
+                .biases
+                .iter_mut()
+                .zip(&grads.biases[l])
+                .zip(layer.bias_moments.iter_mut())
+            {
+                step(b, *g, m);
+            }
+        }
+    }
+}
+
+impl NNBackend for Mlp {
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.forward_all(input).pop().unwrap()
+    }
+
+    /// Runs the whole batch through each layer in turn, giving the same outputs as `forward`
+    fn forward_batch(&self, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        assert!(
+            inputs.iter().all(|x| x.len() == self.layers[0].inputs),
+            "wrong number of inputs"
+        );
+        let mut values: Vec<f32> = inputs.concat();
+        for (l, layer) in self.layers.iter().enumerate() {
+            values = layer.forward_batch(&values, inputs.len(), self.activation(l));
+        }
+        let outputs = self.layers[self.layers.len() - 1].outputs;
+        values.chunks_exact(outputs).map(|x| x.to_vec()).collect()
+    }
+
+    /// Updates once for every `batch_size` chunk
+    fn fit(&mut self, inputs: &[Vec<f32>], outputs: &[Vec<f32>], batch_size: usize) {
+        for (inputs, outputs) in inputs
+            .chunks(batch_size.max(1))
+            .zip(outputs.chunks(batch_size.max(1)))
+        {
+            let grads = self.gradients(inputs, outputs);
+            self.apply(&grads);
+        }
+    }
+
+    /// weights then biases for each layer. Adam state is in `optimizer_snapshot`
+    fn weights(&self) -> Vec<f32> {
+        self.layers
+            .iter()
+            .flat_map(|l| l.weights.iter().chain(l.biases.iter()))
+            .copied()
+            .collect()
+    }
+
+    /// Panics if `weights` is not the length of `weights()`
+    fn set_weights(&mut self, weights: &[f32]) {
+        let expected: usize = self
+            .layers
+            .iter()
+            .map(|l| l.weights.len() + l.biases.len())
+            .sum();
+        assert_eq!(weights.len(), expected, "wrong number of weights");
+        let mut start = 0;
+        for layer in self.layers.iter_mut() {
+            let end = start + layer.weights.len();
+            layer.weights.copy_from_slice(&weights[start..end]);
+            start = end;
+            let end = start + layer.biases.len();
+            layer.biases.copy_from_slice(&weights[start..end]);
+            start = end;
+        }
+    }
+
+    /// Number of updates, then Adam moments for each layer, weights then biases
+    fn optimizer_snapshot(&self) -> Vec<f64> {
+        let moments = self
+            .layers
+            .iter()
+            .flat_map(|l| l.weight_moments.iter().chain(l.bias_moments.iter()))
+            .flat_map(|(m, v)| [*m as f64, *v as f64]);
+        std::iter::once(self.updates as f64)
+            .chain(moments)
+            .collect()
+    }
+
+    fn restore_optimizer(&mut self, snapshot: &[f64]) -> Result<(), String> {
+        let moments: usize = self
+            .layers
+            .iter()
+            .map(|l| l.weight_moments.len() + l.bias_moments.len())
+            .sum();
+        let Some((updates, mut values)) = snapshot.split_first() else {
+            return Err("expected optimizer values, found none".into());
+        };
+        if values.len() != moments * 2 {
+            return Err(format!(
+                "expected {} optimizer values, found {}",
+                moments * 2 + 1,
+                snapshot.len()
+            ));
+        }
+        self.updates = *updates as i32;
+        for layer in self.layers.iter_mut() {
+            for moment in layer
+                .weight_moments
+                .iter_mut()
+                .chain(layer.bias_moments.iter_mut())
+            {
+                *moment = (values[0] as f32, values[1] as f32);
+                values = &values[2..];
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// loss averaged over the batch, which `gradients` is the derivative of
+    fn average_loss(mlp: &Mlp, inputs: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+        let total: f32 = inputs
+            .iter()
+            .zip(targets)
+            .flat_map(|(input, target)| {
+                mlp.forward(input)
+                    .into_iter()
+                    .zip(target.clone())
+                    .map(|(o, t)| {
+                        let diff = o - t;
+                        match mlp.loss {
+                            Loss::Mse => 0.5 * diff * diff,
+                            Loss::Huber(delta) if diff.abs() <= delta => 0.5 * diff * diff,
+                            Loss::Huber(delta) => delta * (diff.abs() - 0.5 * delta),
+                        }
+                    })
+            })
+            .sum();
+        total / inputs.len() as f32
+    }
+
+    #[test]
+    fn gradients_match_finite_differences() {
+        fastrand::seed(1);
+        let inputs: Vec<Vec<f32>> = (0..4)
+            .map(|_| (0..3).map(|_| fastrand::f32() * 2. - 1.).collect())
+            .collect();
+        let targets: Vec<Vec<f32>> = (0..4)
+            .map(|_| (0..2).map(|_| fastrand::f32() * 4. - 2.).collect())
+            .collect();
+        for hidden in [Activation::Sigmoid, Activation::Tanh, Activation::Linear] {
+            for loss in [Loss::Mse, Loss::Huber(0.5)] {
+                let mut mlp = Mlp::new(&[3, 5, 4, 2], 0.1)
+                    .with_hidden(hidden)
+                    .with_loss(loss);
+                let grads = mlp.gradients(&inputs, &targets);
+                let analytic: Vec<f32> = grads
+                    .weights
+                    .iter()
+                    .zip(&grads.biases)
+                    .flat_map(|(w, b)| w.iter().chain(b.iter()))
+                    .copied()
+                    .collect();
+
+                let weights = mlp.weights();
+                assert_eq!(analytic.len(), weights.len());
+                let epsilon = 1e-3;
+                for (i, analytic) in analytic.iter().enumerate() {
+                    let mut changed = weights.clone();
+                    changed[i] = weights[i] + epsilon;
+                    mlp.set_weights(&changed);
+                    let up = average_loss(&mlp, &inputs, &targets);
+                    changed[i] = weights[i] - epsilon;
+                    mlp.set_weights(&changed);
+                    let down = average_loss(&mlp, &inputs, &targets);
+                    let numeric = (up - down) / (2. * epsilon);
+                    assert!(
+                        (numeric - analytic).abs() <= 2e-3 + 2e-2 * analytic.abs(),
+                        "{hidden:?} {loss:?} weight {i}: numeric {numeric}, backprop {analytic}"
+                    );
+                }
+                mlp.set_weights(&weights);
+            }
+        }
+    }
+
+    #[test]
+    fn forward_batch_matches_forward() {
+        fastrand::seed(5);
+        let mlp = Mlp::new(&[3, 6, 4, 2], 0.1).with_hidden(Activation::Relu);
+        let inputs: Vec<Vec<f32>> = (0..7)
+            .map(|_| (0..3).map(|_| fastrand::f32() * 2. - 1.).collect())
+            .collect();
+        let single: Vec<Vec<f32>> = inputs.iter().map(|x| mlp.forward(x)).collect();
+        assert_eq!(mlp.forward_batch(&inputs), single);
+        assert!(mlp.forward_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn learns_xor_with_adam() {
+        fastrand::seed(2);
+        let inputs = vec![vec![0., 0.], vec![0., 1.], vec![1., 0.], vec![1., 1.]];
+        let targets = vec![vec![0.], vec![1.], vec![1.], vec![0.]];
+        let mut mlp = Mlp::new(&[2, 8, 1], 0.05)
+            .with_hidden(Activation::Tanh)
+            .with_optimizer(Optimizer::adam());
+        for _ in 0..500 {
+            mlp.fit(&inputs, &targets, 4);
+        }
+        for (input, target) in inputs.iter().zip(&targets) {
+            let output = mlp.forward(input)[0];
+            assert!((output - target[0]).abs() < 0.1, "{input:?} gave {output}");
+        }
+    }
+
+    #[test]
+    fn fits_a_curve_with_sgd() {
+        fastrand::seed(3);
+        let inputs: Vec<Vec<f32>> = (0..21).map(|i| vec![i as f32 / 10. - 1.]).collect();
+        let targets: Vec<Vec<f32>> = inputs.iter().map(|x| vec![x[0] * x[0]]).collect();
+        let mut mlp = Mlp::new(&[1, 16, 1], 0.1);
+        let before = average_loss(&mlp, &inputs, &targets);
+        for _ in 0..3000 {
+            mlp.fit(&inputs, &targets, 7);
+        }
+        let after = average_loss(&mlp, &inputs, &targets);
+        assert!(after < 0.001, "loss went from {before} to {after}");
+    }
+}
@@ -1,16 +1,35 @@
 use fxhash::FxHasher;
 
 use crate::{
-    agent::Agent, environment::Environment, progress::Progress, strategy::ExploreStrategy,
+    agent::{Agent, JointAgent, ParallelAgent, Policy, Transition},
+    environment::Environment,
+    progress::Progress,
+    strategy::ExploreStrategy,
 };
 use core::hash::Hash;
 use std::{collections::HashMap, hash::BuildHasherDefault};
+
+#[cfg(feature = "serde")]
+use crate::{
+    agent::Checkpoint,
+    persist::{self, Format, PersistError},
+};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+pub type QTable<S, A> =
+    HashMap<S, HashMap<A, f64, BuildHasherDefault<FxHasher>>, BuildHasherDefault<FxHasher>>;
+
 pub struct QLearning<S, A> {
-    pub qtable:
-        HashMap<S, HashMap<A, f64, BuildHasherDefault<FxHasher>>, BuildHasherDefault<FxHasher>>,
+    pub qtable: QTable<S, A>,
     alpha: f64,
     gamma: f64,
-    strategy: Box<(dyn ExploreStrategy<A> + Send)>,
+    strategy: Box<dyn ExploreStrategy<A> + Send>,
 }
 
 impl<S, A> QLearning<S, A> {
@@ -26,6 +45,89 @@ impl<S, A> QLearning<S, A> {
     }
 }
 
+/// What gets saved to file. `S` and `A` are borrowed when saving and owned when loading
+/// The qtable is saved as a list, because json only allows string keys in maps
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct QLearningFile<S, A> {
+    qtable: Vec<(S, Vec<(A, f64)>)>,
+    alpha: f64,
+    gamma: f64,
+    strategy: Vec<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl<S, A> QLearning<S, A>
+where
+    S: Hash + Eq + Serialize + DeserializeOwned,
+    A: Hash + Eq + Serialize + DeserializeOwned,
+{
+    const KIND: &'static str = "qlearning";
+
+    /// Saves qtable, alpha, gamma and strategy state
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        persist::save(path, format, Self::KIND, &self.to_file())
+    }
+
+    /// Loads a file saved with `save`.
+    /// The strategy cannot be saved itself, so pass in the same type of strategy and its state is restored
+    pub fn load(
+        path: impl AsRef<Path>,
+        format: Format,
+        strategy: impl ExploreStrategy<A> + Send + 'static,
+    ) -> Result<Self, PersistError> {
+        let mut agent = Self::new(0., 0., strategy);
+        agent.load_file(persist::load(path, format, Self::KIND)?)?;
+        Ok(agent)
+    }
+
+    fn to_file(&self) -> QLearningFile<&S, &A> {
+        QLearningFile {
+            qtable: self
+                .qtable
+                .iter()
+                .map(|(s, actions)| (s, actions.iter().map(|(a, q)| (a, *q)).collect()))
+                .collect(),
+            alpha: self.alpha,
+            gamma: self.gamma,
+            strategy: self.strategy.snapshot(),
+        }
+    }
+
+    fn load_file(&mut self, file: QLearningFile<S, A>) -> Result<(), PersistError> {
+        self.qtable = file
+            .qtable
+            .into_iter()
+            .map(|(s, actions)| (s, actions.into_iter().collect()))
+            .collect();
+        self.alpha = file.alpha;
+        self.gamma = file.gamma;
+        self.strategy
+            .restore(&file.strategy)
+            .map_err(PersistError::Format)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S, A> Checkpoint for QLearning<S, A>
+where
+    S: Hash + Eq + Serialize + DeserializeOwned,
+    A: Hash + Eq + Serialize + DeserializeOwned,
+{
+    fn save_checkpoint(&self, writer: &mut dyn Write) -> Result<(), PersistError> {
+        persist::to_writer(writer, Format::Binary, Self::KIND, &self.to_file())
+    }
+
+    /// Keeps the alpha and gamma this agent was created with, so they can differ from the saved agent
+    fn load_checkpoint(&mut self, reader: &mut dyn Read) -> Result<(), PersistError> {
+        let (alpha, gamma) = (self.alpha, self.gamma);
+        self.load_file(persist::from_reader(reader, Format::Binary, Self::KIND)?)?;
+        self.alpha = alpha;
+        self.gamma = gamma;
+        Ok(())
+    }
+}
+
 impl<S, A> Agent<S, A> for QLearning<S, A>
 where
     S: Clone + Hash + Eq,
@@ -40,44 +142,218 @@ where
         self.strategy.pick_action(actions, best, progress)
     }
 
+    fn exploration(&self) -> Option<f64> {
+        self.strategy.exploration()
+    }
+
     /// Trains 1 epoch
     fn step(&mut self, progress: Progress, env: &mut dyn Environment<S, A>) -> bool {
         let current_state = env.state();
-        let best = self.qtable.get(&current_state).and_then(|x| {
-            x.iter()
-                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-                .map(|x| x.0.to_owned())
-        });
         let actions = env.all_actions();
         if actions.is_empty() {
             return true;
         }
+        let best = best_action(&self.qtable, &current_state, &actions);
         let action = self.strategy.pick_action(&actions, best, progress);
 
+        let reward = env.take_action_get_reward(&action);
+        let new_state = env.state();
+        let done = env.should_stop(progress.epoch_step);
+
+        self.update(current_state, action, reward, &new_state, env.terminated());
+
+        done
+    }
+}
+
+impl<S, A> QLearning<S, A>
+where
+    S: Hash + Eq,
+    A: Hash + Eq,
+{
+    /// There is no next value if `terminated`, but there is when the epoch was only cut short
+    fn update(&mut self, state: S, action: A, reward: f64, new_state: &S, terminated: bool) {
         let default_value = 0.;
         let oldq = self
             .qtable
-            .get(&current_state)
+            .get(&state)
             .and_then(|x| x.get(&action))
             .unwrap_or(&default_value);
 
-        let reward = env.take_action_get_reward(&action);
-        let new_state = env.state();
-        let done = env.should_stop(progress.epoch_step);
+        let maxq_at_new_state = match terminated {
+            true => &default_value,
+            false => self
+                .qtable
+                .get(new_state)
+                .and_then(|x| x.values().max_by(|a, b| a.partial_cmp(b).unwrap()))
+                .unwrap_or(&default_value),
+        };
+
+        let newq = oldq + self.alpha * (reward + self.gamma * maxq_at_new_state - oldq);
+
+        self.qtable.entry(state).or_default().insert(action, newq);
+    }
+}
+
+/// Go through in order of `actions`, so that ties do not depend on the qtable's order.
+/// This means a restored qtable picks the same actions
+fn best_action<S, A>(qtable: &QTable<S, A>, state: &S, actions: &[A]) -> Option<A>
+where
+    S: Hash + Eq,
+    A: Clone + Hash + Eq,
+{
+    qtable.get(state).and_then(|x| {
+        actions
+            .iter()
+            .filter_map(|a| x.get(a).map(|q| (a, q)))
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|x| x.0.to_owned())
+    })
+}
+
+/// Workers pick actions with a copy of the qtable, which is updated every `ParallelConfig::sync_steps`
+impl<S, A> ParallelAgent<S, A> for QLearning<S, A>
+where
+    S: Clone + Hash + Eq + Send + Sync,
+    A: Clone + Hash + Eq + Send + Sync,
+{
+    type Observation = S;
+    type Actor = QTable<S, A>;
+
+    fn observe(env: &mut dyn Environment<S, A>) -> S {
+        env.state()
+    }
+
+    fn actor(&self) -> QTable<S, A> {
+        self.qtable.clone()
+    }
+
+    fn best_action(actor: &QTable<S, A>, observation: &S, actions: &[A]) -> Option<A> {
+        best_action(actor, observation, actions)
+    }
+
+    fn learn(&mut self, transition: Transition<S, A>, _progress: Progress) {
+        self.update(
+            transition.state,
+            transition.action,
+            transition.reward,
+            &transition.next_state,
+            transition.terminated,
+        );
+    }
+}
+
+impl<S, A> JointAgent<S, A> for QLearning<S, A>
+where
+    S: Clone + Hash + Eq + Send + Sync,
+    A: Clone + Hash + Eq + Send + Sync,
+{
+    fn best(&self, observation: &S, actions: &[A]) -> Option<A> {
+        best_action(&self.qtable, observation, actions)
+    }
+}
+
+impl<S, A> Policy<S, A> for QLearning<S, A>
+where
+    S: Hash + Eq,
+    A: Hash + Eq,
+{
+    /// Actions not yet in the qtable have a value of 0
+    fn action_values(&self, state: &S, actions: &[A]) -> Vec<f64> {
+        let values = self.qtable.get(state);
+        actions
+            .iter()
+            .map(|a| values.and_then(|x| x.get(a)).copied().unwrap_or_default())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{runner::Runner, strategy::decliningrandom::DecliningRandom};
+
+    /// One state and one action with a reward of 1, stopped after every step
+    struct Loop {
+        terminal: bool,
+    }
 
-        let maxq_at_new_state = self
+    impl Environment<u8, u8> for Loop {
+        fn state(&self) -> u8 {
+            0
+        }
+        fn reset(&mut self, _epoch: usize) {}
+        fn all_actions(&self) -> Vec<u8> {
+            vec![0]
+        }
+        fn take_action_get_reward(&mut self, _action: &u8) -> f64 {
+            1.
+        }
+        fn should_stop(&mut self, _step: usize) -> bool {
+            true
+        }
+        fn terminated(&self) -> bool {
+            self.terminal
+        }
+    }
+
+    fn value(terminal: bool) -> f64 {
+        let agent = QLearning::new(0.5, 0.9, DecliningRandom::new(1, 0.));
+        let (agent, _) = Runner::run_with_metrics(agent, Loop { terminal }, 1000);
+        agent.qtable[&0][&0]
+    }
+
+    #[test]
+    fn bootstraps_unless_terminated() {
+        //cut short, so the state's value is the discounted sum of every future reward
+        assert!((value(false) - 10.).abs() < 1e-6);
+        assert!((value(true) - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn policy_reads_the_qtable() {
+        let mut agent: QLearning<u8, u8> = QLearning::new(0.5, 0.9, DecliningRandom::new(1, 0.));
+        agent
             .qtable
-            .get(&new_state)
-            .and_then(|x| x.values().max_by(|a, b| a.partial_cmp(b).unwrap()))
-            .unwrap_or(&default_value);
+            .insert(0, [(1, 0.5), (2, -1.)].into_iter().collect());
+        assert_eq!(agent.action_values(&0, &[0, 1, 2]), vec![0., 0.5, -1.]);
+        assert_eq!(agent.act(&0, &[0, 1, 2]), 1);
+        assert_eq!(agent.act(&0, &[2, 0]), 0);
+        //a state which was never seen has no value for any action
+        assert_eq!(agent.action_values(&1, &[1, 2]), vec![0., 0.]);
+    }
 
-        let newq = oldq + self.alpha * (reward + self.gamma * maxq_at_new_state - oldq);
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut agent: QLearning<(i32, i32), u8> =
+            QLearning::new(0.3, 0.8, DecliningRandom::new(10, 0.1));
+        agent
+            .qtable
+            .insert((1, -2), [(0, 0.25), (3, -1.5)].into_iter().collect());
+        agent.qtable.insert((0, 0), [(1, 7.)].into_iter().collect());
+        agent.strategy.restore(&[10., 4., 0.1]).unwrap();
 
-        self.qtable
-            .entry(current_state.clone())
-            .or_insert_with(HashMap::default)
-            .insert(action.clone(), newq);
+        let path = std::env::temp_dir().join(format!("learnwell-qtable-{}", std::process::id()));
+        for format in [Format::Binary, Format::Json] {
+            agent.save(&path, format).unwrap();
+            let loaded: QLearning<(i32, i32), u8> =
+                QLearning::load(&path, format, DecliningRandom::new(10, 0.1)).unwrap();
+            assert_eq!(loaded.qtable, agent.qtable);
+            assert_eq!((loaded.alpha, loaded.gamma), (0.3, 0.8));
+            assert_eq!(loaded.strategy.snapshot(), agent.strategy.snapshot());
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
 
-        done
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_of_other_kind_is_an_error() {
+        let path = std::env::temp_dir().join(format!("learnwell-other-{}", std::process::id()));
+        persist::save(&path, Format::Json, "deepqlearning", &1u32).unwrap();
+        let result: Result<QLearning<u8, u8>, _> =
+            QLearning::load(&path, Format::Json, DecliningRandom::new(1, 0.));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(PersistError::Kind { .. })));
     }
 }
@@ -1,4 +1,11 @@
-use show_image::ImageView;
+use std::borrow::Cow;
+
+pub mod checker;
+pub(crate) mod draw;
+pub mod multiagent;
+pub mod turnbased;
+pub mod vecenv;
+pub mod wrappers;
 
 pub trait Environment<S, A> {
     /// Gets the state
@@ -11,6 +18,342 @@ pub trait Environment<S, A> {
     fn take_action_get_reward(&mut self, action: &A) -> f64;
     /// Should we stop based on state or step count
     fn should_stop(&mut self, step: usize) -> bool;
-    ///if you wish to display environment, or use deep qlearning, we implement this. otherwise return default
-    fn get_image(&mut self) -> ImageView;
+    /// Whether the current state ends the epoch by itself e.g. the pole fell, as opposed to `should_stop`
+    /// cutting it short after a number of steps. `QLearning` adds no value for the next state after a terminal state,
+    /// but still does when an epoch is cut short. Defaults to false, so the next state's value is always added
+    fn terminated(&self) -> bool {
+        false
+    }
+    /// Last reward before a wrapper such as `PotentialShaping` changed it, so runners can record
+    /// `Metrics::raw_returns`. `None` if rewards are not shaped
+    fn unshaped_reward(&self) -> Option<f64> {
+        None
+    }
+    /// Expected reward lost so far by not picking the best action, for environments which know it e.g. bandits.
+    /// Recorded by runners in `Metrics::regrets`. `None` if unknown
+    fn regret(&self) -> Option<f64> {
+        None
+    }
+    ///if you wish to display environment, or use deep qlearning, we implement this. otherwise leave as default
+    fn get_image(&mut self) -> Frame<'_> {
+        Frame::default()
+    }
+    ///input for deep learning. Defaults to the image (see `Frame::to_tensor`),
+    ///but environments with numeric state can return features directly e.g. positions and velocities
+    fn observation(&mut self) -> Vec<f32> {
+        self.get_image().to_tensor()
+    }
+    ///if you wish to watch the environment in a terminal with `Runner::run_with_text`, we implement this.
+    ///Can include ANSI colour codes
+    fn render_text(&self) -> String {
+        String::new()
+    }
+}
+
+/// So runners can borrow an environment, and it can be used again afterwards
+impl<S, A, E: Environment<S, A> + ?Sized> Environment<S, A> for &mut E {
+    fn state(&self) -> S {
+        (**self).state()
+    }
+
+    fn reset(&mut self, epoch: usize) {
+        (**self).reset(epoch)
+    }
+
+    fn all_actions(&self) -> Vec<A> {
+        (**self).all_actions()
+    }
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        (**self).take_action_get_reward(action)
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        (**self).should_stop(step)
+    }
+
+    fn terminated(&self) -> bool {
+        (**self).terminated()
+    }
+
+    fn unshaped_reward(&self) -> Option<f64> {
+        (**self).unshaped_reward()
+    }
+
+    fn regret(&self) -> Option<f64> {
+        (**self).regret()
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        (**self).get_image()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        (**self).observation()
+    }
+
+    fn render_text(&self) -> String {
+        (**self).render_text()
+    }
+}
+
+/// Image of the environment, used for display and as input for deep learning.
+/// Data can be borrowed from the environment, or owned e.g. after converting to grayscale.
+/// Made with `rgb` or `gray`, so there are always 3 or 1 channels
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame<'a> {
+    width: usize,
+    height: usize,
+    /// 3 for rgb, 1 for grayscale
+    channels: usize,
+    /// `channels` bytes per pixel, row by row
+    data: Cow<'a, [u8]>,
+}
+
+/// Empty rgb frame, for environments without an image
+impl Default for Frame<'_> {
+    fn default() -> Self {
+        Self::rgb(0, 0, vec![])
+    }
+}
+
+impl<'a> Frame<'a> {
+    pub fn rgb(width: usize, height: usize, data: impl Into<Cow<'a, [u8]>>) -> Self {
+        Self::new(width, height, 3, data.into())
+    }
+
+    pub fn gray(width: usize, height: usize, data: impl Into<Cow<'a, [u8]>>) -> Self {
+        Self::new(width, height, 1, data.into())
+    }
+
+    fn new(width: usize, height: usize, channels: usize, data: Cow<'a, [u8]>) -> Self {
+        assert_eq!(
+            width * height * channels,
+            data.len(),
+            "data does not match size"
+        );
+        Self {
+            width,
+            height,
+            channels,
+            data,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 3 for rgb, 1 for grayscale
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// `channels` bytes per pixel, row by row
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// bytes of pixel at `x`,`y`
+    pub fn pixel(&self, x: usize, y: usize) -> &[u8] {
+        let start = (y * self.width + x) * self.channels;
+        &self.data[start..start + self.channels]
+    }
+
+    /// Copies data so frame no longer borrows from the environment
+    pub fn into_owned(self) -> Frame<'static> {
+        Frame {
+            width: self.width,
+            height: self.height,
+            channels: self.channels,
+            data: Cow::Owned(self.data.into_owned()),
+        }
+    }
+
+    /// Grayscale using luminance, which makes deep learning input 3 times smaller
+    pub fn to_gray(&self) -> Frame<'static> {
+        let data = match self.channels {
+            1 => self.data.to_vec(),
+            _ => self
+                .data
+                .chunks(self.channels)
+                .map(|p| (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) as u8)
+                .collect(),
+        };
+        Frame::gray(self.width, self.height, data)
+    }
+
+    pub fn to_rgb(&self) -> Frame<'static> {
+        let data = match self.channels {
+            1 => self.data.iter().flat_map(|&x| [x, x, x]).collect(),
+            _ => self.data.to_vec(),
+        };
+        Frame::rgb(self.width, self.height, data)
+    }
+
+    /// Nearest neighbour upscale, so each pixel becomes `factor` x `factor` pixels
+    pub fn scale(&self, factor: usize) -> Frame<'static> {
+        let factor = factor.max(1);
+        let width = self.width * factor;
+        let data = (0..self.height * factor)
+            .flat_map(|y| (0..width).map(move |x| (x / factor, y / factor)))
+            .flat_map(|(x, y)| self.pixel(x, y).to_vec())
+            .collect();
+        Frame::new(width, self.height * factor, self.channels, Cow::Owned(data))
+    }
+
+    /// Values as f32 in the same order as data, used as input to a neural network
+    pub fn to_tensor(&self) -> Vec<f32> {
+        self.data.iter().map(|x| *x as f32).collect()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, writer: impl std::io::Write) -> std::io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(match self.channels {
+            1 => png::ColorType::Grayscale,
+            _ => png::ColorType::Rgb,
+        });
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.data)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_png(file)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a> From<&'a Frame<'_>> for show_image::ImageView<'a> {
+    fn from(frame: &'a Frame<'_>) -> Self {
+        let (width, height) = (frame.width as u32, frame.height as u32);
+        let info = match frame.channels {
+            1 => show_image::ImageInfo::mono8(width, height),
+            _ => show_image::ImageInfo::rgb8(width, height),
+        };
+        show_image::ImageView::new(info, &frame.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Environment with nothing to show
+    struct Blank;
+
+    impl Environment<u8, u8> for Blank {
+        fn state(&self) -> u8 {
+            0
+        }
+        fn reset(&mut self, _epoch: usize) {}
+        fn all_actions(&self) -> Vec<u8> {
+            vec![0]
+        }
+        fn take_action_get_reward(&mut self, _action: &u8) -> f64 {
+            0.
+        }
+        fn should_stop(&mut self, _step: usize) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn no_image_by_default() {
+        assert!(Blank.get_image().is_empty());
+        assert!(Blank.observation().is_empty());
+    }
+
+    #[test]
+    fn pixels_are_row_by_row() {
+        let data = [1, 2, 3, 4, 5, 6];
+        let frame = Frame::gray(3, 2, &data[..]);
+        assert_eq!(frame.pixel(2, 0), [3]);
+        assert_eq!(frame.pixel(0, 1), [4]);
+        let frame = Frame::rgb(2, 1, &data[..]);
+        assert_eq!(frame.pixel(1, 0), [4, 5, 6]);
+        assert_eq!(frame.clone().into_owned(), frame);
+    }
+
+    #[test]
+    #[should_panic(expected = "data does not match size")]
+    fn data_must_match_size() {
+        Frame::rgb(2, 2, vec![0; 4]);
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn shows_gray_and_rgb() {
+        let gray = Frame::gray(2, 1, vec![7, 8]);
+        let view = show_image::ImageView::from(&gray);
+        assert_eq!(view.info(), show_image::ImageInfo::mono8(2, 1));
+        assert_eq!(view.data(), [7, 8]);
+        let rgb = gray.to_rgb();
+        let view = show_image::ImageView::from(&rgb);
+        assert_eq!(view.info(), show_image::ImageInfo::rgb8(2, 1));
+        assert_eq!(view.data(), [7, 7, 7, 8, 8, 8]);
+    }
+
+    #[test]
+    fn gray_uses_luminance_weights() {
+        let data = [255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255];
+        let gray = Frame::rgb(4, 1, &data[..]).to_gray();
+        assert_eq!(gray.channels(), 1);
+        assert_eq!(gray.data(), [76, 149, 29, 255]);
+        assert_eq!(gray.to_gray(), gray);
+    }
+
+    #[test]
+    fn scale_repeats_pixels_up_to_the_edges() {
+        let frame = Frame::gray(2, 2, vec![1, 2, 3, 4]).scale(2);
+        assert_eq!((frame.width(), frame.height()), (4, 4));
+        #[rustfmt::skip]
+        assert_eq!(frame.data(), [
+            1, 1, 2, 2,
+            1, 1, 2, 2,
+            3, 3, 4, 4,
+            3, 3, 4, 4,
+        ]);
+        let rgb = Frame::rgb(1, 1, vec![5, 6, 7]);
+        assert_eq!(rgb.scale(3).pixel(2, 2), [5, 6, 7]);
+        assert_eq!(rgb.scale(0), rgb);
+    }
+
+    #[test]
+    fn tensor_keeps_byte_order() {
+        let frame = Frame::rgb(2, 1, vec![0, 1, 2, 253, 254, 255]);
+        assert_eq!(frame.to_tensor(), [0., 1., 2., 253., 254., 255.]);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_decodes_to_same_pixels() {
+        for frame in [
+            Frame::rgb(2, 1, vec![10, 20, 30, 40, 50, 60]),
+            Frame::gray(1, 2, vec![0, 255]),
+        ] {
+            let mut bytes = vec![];
+            frame.write_png(&mut bytes).unwrap();
+            let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+            let mut buffer = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut buffer).unwrap();
+            assert_eq!(
+                (info.width, info.height),
+                (frame.width() as u32, frame.height() as u32)
+            );
+            assert_eq!(info.color_type.samples(), frame.channels());
+            assert_eq!(&buffer[..info.buffer_size()], frame.data());
+        }
+    }
 }
@@ -0,0 +1,346 @@
+//! Drives an environment with random actions to find mistakes in implementing `Environment`
+//! before spending time training on it e.g.
+//! ```ignore
+//! let report = checker::check(TaxiEnvironment::default, &CheckConfig::default());
+//! println!("{report}");
+//! ```
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use super::Environment;
+
+pub struct CheckConfig {
+    pub epochs: usize,
+    /// epochs which have not stopped after this many steps fail the check
+    pub max_steps: usize,
+    /// random seed for both runs, which are compared to check they are the same
+    pub seed: u64,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 10,
+            max_steps: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+/// One invariant, and the first time it did not hold
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    /// `None` if it always held
+    pub failure: Option<String>,
+}
+
+/// Result of every check. Prints as a line for each
+#[derive(Clone, Debug)]
+pub struct CheckReport {
+    pub epochs: usize,
+    pub steps: usize,
+    pub longest_epoch: usize,
+    pub checks: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    /// Whether every check held
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|x| x.failure.is_none())
+    }
+}
+
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} epochs, {} steps, longest epoch {} steps",
+            self.epochs, self.steps, self.longest_epoch
+        )?;
+        for check in &self.checks {
+            match &check.failure {
+                None => writeln!(f, "  ok      {}", check.name)?,
+                Some(failure) => writeln!(f, "  FAILED  {}: {failure}", check.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `config.epochs` of the environment from `make` with random actions, checking that:
+/// - there is always an action, and always the same number of them, which `DeepQLearning` needs
+/// - the image and observation are always the same size
+/// - rewards are finite
+/// - epochs stop within `config.max_steps`
+/// - running again from the same seed gives the same states, actions, rewards and observations
+///
+/// `reset` is called before every epoch and `should_stop` after every step, as the runners do.
+/// The random seed is put back afterwards
+pub fn check<S: PartialEq, A, E: Environment<S, A>>(
+    make: impl Fn() -> E,
+    config: &CheckConfig,
+) -> CheckReport {
+    let seed = fastrand::get_seed();
+    fastrand::seed(config.seed);
+    let first = trace(&mut make(), config);
+    fastrand::seed(config.seed);
+    let second = trace(&mut make(), config);
+    fastrand::seed(seed);
+
+    let steps: Vec<(usize, usize, &Step<S>)> = first
+        .iter()
+        .enumerate()
+        .flat_map(|(epoch, steps)| {
+            steps
+                .iter()
+                .enumerate()
+                .map(move |(i, x)| (epoch + 1, i, x))
+        })
+        .collect();
+    let Some(&(_, _, start)) = steps.first() else {
+        return CheckReport {
+            epochs: 0,
+            steps: 0,
+            longest_epoch: 0,
+            checks: vec![],
+        };
+    };
+    let find = |f: &dyn Fn(&Step<S>) -> Option<String>| {
+        steps
+            .iter()
+            .find_map(|&(epoch, i, step)| f(step).map(|x| format!("epoch {epoch} step {i}, {x}")))
+    };
+
+    let checks = vec![
+        CheckResult {
+            name: "actions are never empty".into(),
+            failure: find(&|x| (!x.done && x.actions == 0).then(|| "no actions".into())),
+        },
+        CheckResult {
+            name: "number of actions never changes".into(),
+            failure: find(&|x| {
+                (!x.done && x.actions != start.actions)
+                    .then(|| format!("{} actions instead of {}", x.actions, start.actions))
+            }),
+        },
+        CheckResult {
+            name: "image size never changes".into(),
+            failure: find(&|x| {
+                (x.image != start.image).then(|| {
+                    let [w, h, c] = x.image;
+                    let [sw, sh, sc] = start.image;
+                    format!("{w}x{h}x{c} instead of {sw}x{sh}x{sc}")
+                })
+            }),
+        },
+        CheckResult {
+            name: "observation length never changes".into(),
+            failure: find(&|x| {
+                (x.observation_len != start.observation_len).then(|| {
+                    format!(
+                        "{} values instead of {}",
+                        x.observation_len, start.observation_len
+                    )
+                })
+            }),
+        },
+        CheckResult {
+            name: "rewards are finite".into(),
+            failure: find(&|x| (!x.reward.is_finite()).then(|| format!("reward {}", x.reward))),
+        },
+        CheckResult {
+            name: format!("epochs stop within {} steps", config.max_steps),
+            failure: first.iter().enumerate().find_map(|(epoch, steps)| {
+                let stopped = steps.last().is_some_and(|x| x.done || x.actions == 0);
+                (!stopped).then(|| format!("epoch {} did not stop", epoch + 1))
+            }),
+        },
+        CheckResult {
+            name: "same seed gives the same run".into(),
+            failure: compare(&first, &second),
+        },
+    ];
+    CheckReport {
+        epochs: first.len(),
+        steps: first.iter().map(|x| x.len() - 1).sum(),
+        longest_epoch: first.iter().map(|x| x.len() - 1).max().unwrap_or(0),
+        checks,
+    }
+}
+
+/// What happened at a step, or after reset
+struct Step<S> {
+    state: S,
+    /// number of actions available to pick from next. Not checked once done
+    actions: usize,
+    /// index of the action which led here, and its reward
+    action: usize,
+    reward: f64,
+    done: bool,
+    /// width, height and channels
+    image: [usize; 3],
+    observation_len: usize,
+    /// hash of the observation, which can be too big to keep for every step
+    observation: u64,
+}
+
+/// Steps of each epoch, starting with the state after reset
+fn trace<S, A, E: Environment<S, A>>(
+    environment: &mut E,
+    config: &CheckConfig,
+) -> Vec<Vec<Step<S>>> {
+    let mut epochs = vec![];
+    for epoch in 1..=config.epochs {
+        environment.reset(epoch);
+        let mut steps = vec![record(environment, 0, 0., false)];
+        for step in 1..=config.max_steps {
+            let actions = environment.all_actions();
+            if actions.is_empty() {
+                break;
+            }
+            let index = fastrand::usize(..actions.len());
+            let reward = environment.take_action_get_reward(&actions[index]);
+            let done = environment.should_stop(step);
+            steps.push(record(environment, index, reward, done));
+            if done {
+                break;
+            }
+        }
+        epochs.push(steps);
+    }
+    epochs
+}
+
+fn record<S, A, E: Environment<S, A>>(
+    environment: &mut E,
+    action: usize,
+    reward: f64,
+    done: bool,
+) -> Step<S> {
+    let image = environment.get_image();
+    let image = [image.width(), image.height(), image.channels()];
+    let observation = environment.observation();
+    let mut hasher = DefaultHasher::new();
+    for x in &observation {
+        x.to_bits().hash(&mut hasher);
+    }
+    Step {
+        state: environment.state(),
+        actions: environment.all_actions().len(),
+        action,
+        reward,
+        done,
+        image,
+        observation_len: observation.len(),
+        observation: hasher.finish(),
+    }
+}
+
+/// First difference between two runs, if any
+fn compare<S: PartialEq>(first: &[Vec<Step<S>>], second: &[Vec<Step<S>>]) -> Option<String> {
+    for (epoch, (a, b)) in first.iter().zip(second).enumerate() {
+        let epoch = epoch + 1;
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            let difference = if a.state != b.state {
+                "state differs".to_string()
+            } else if a.actions != b.actions {
+                format!("{} actions then {}", a.actions, b.actions)
+            } else if a.action != b.action {
+                "random action differs, so the environment uses the random generator differently"
+                    .to_string()
+            } else if a.reward.to_bits() != b.reward.to_bits() {
+                format!("reward {} then {}", a.reward, b.reward)
+            } else if a.done != b.done {
+                format!("stopped {} then {}", a.done, b.done)
+            } else if a.observation != b.observation {
+                "observation differs".to_string()
+            } else {
+                continue;
+            };
+            return Some(format!("epoch {epoch} step {i}, {difference}"));
+        }
+        if a.len() != b.len() {
+            return Some(format!(
+                "epoch {epoch} took {} steps then {}",
+                a.len() - 1,
+                b.len() - 1
+            ));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{environment::Frame, envs::taxi::TaxiEnvironment};
+
+    /// Image grows a pixel every step, and the third reward is NaN
+    struct Broken {
+        steps: usize,
+        pixels: Vec<u8>,
+    }
+
+    impl Environment<usize, ()> for Broken {
+        fn state(&self) -> usize {
+            self.steps
+        }
+        fn reset(&mut self, _epoch: usize) {
+            self.steps = 0;
+        }
+        fn all_actions(&self) -> Vec<()> {
+            vec![()]
+        }
+        fn take_action_get_reward(&mut self, _action: &()) -> f64 {
+            self.steps += 1;
+            match self.steps {
+                3 => f64::NAN,
+                _ => 1.,
+            }
+        }
+        fn should_stop(&mut self, step: usize) -> bool {
+            step >= 5
+        }
+        fn get_image(&mut self) -> Frame<'_> {
+            self.pixels = vec![0; self.steps + 1];
+            Frame::gray(self.steps + 1, 1, self.pixels.as_slice())
+        }
+    }
+
+    fn failure<'a>(report: &'a CheckReport, name: &str) -> Option<&'a str> {
+        let check = report.checks.iter().find(|x| x.name == name).unwrap();
+        check.failure.as_deref()
+    }
+
+    #[test]
+    fn flags_broken_environment() {
+        let make = || Broken {
+            steps: 0,
+            pixels: vec![],
+        };
+        let report = check(make, &CheckConfig::default());
+        assert!(!report.is_ok());
+        assert_eq!(
+            failure(&report, "image size never changes"),
+            Some("epoch 1 step 1, 2x1x1 instead of 1x1x1")
+        );
+        assert_eq!(
+            failure(&report, "rewards are finite"),
+            Some("epoch 1 step 3, reward NaN")
+        );
+        assert_eq!(failure(&report, "actions are never empty"), None);
+        assert_eq!(failure(&report, "same seed gives the same run"), None);
+    }
+
+    #[test]
+    fn taxi_passes() {
+        let report = check(TaxiEnvironment::default, &CheckConfig::default());
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(report.epochs, 10);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::environment::Frame;
+
+pub(crate) type Color = [u8; 3];
+
+/// Glyphs are 3 pixels wide and 5 high, with a gap of 1 pixel between characters
+pub(crate) const GLYPH_WIDTH: usize = 4;
+pub(crate) const GLYPH_HEIGHT: usize = 5;
+
+/// Rgb image we can draw lines and text on, for charts and to add to environment images
+pub(crate) struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Color) -> Self {
+        Self {
+            width,
+            height,
+            data: background.repeat(width * height),
+        }
+    }
+
+    /// Copies `frame` with top left at `x`,`y`. Pixels outside the canvas are ignored
+    #[cfg(feature = "display")]
+    pub fn draw_frame(&mut self, frame: &Frame, x: usize, y: usize) {
+        let frame = frame.to_rgb();
+        for fy in 0..frame.height() {
+            for fx in 0..frame.width() {
+                let pixel = frame.pixel(fx, fy);
+                self.set(
+                    (x + fx) as i64,
+                    (y + fy) as i64,
+                    [pixel[0], pixel[1], pixel[2]],
+                );
+            }
+        }
+    }
+
+    pub fn into_frame(self) -> Frame<'static> {
+        Frame::rgb(self.width, self.height, self.data)
+    }
+
+    /// Ignores pixels outside the canvas
+    pub fn set(&mut self, x: i64, y: i64, color: Color) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let start = (y as usize * self.width + x as usize) * 3;
+        self.data[start..start + 3].copy_from_slice(&color);
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        for yy in y..y + height {
+            for xx in x..x + width {
+                self.set(xx as i64, yy as i64, color);
+            }
+        }
+    }
+
+    pub fn line(&mut self, from: (i64, i64), to: (i64, i64), color: Color) {
+        //Bresenham
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let sx = if x < to.0 { 1 } else { -1 };
+        let sy = if y < to.1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x, y, color);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draws `text` with top left at `x`,`y`, each font pixel `scale` pixels wide.
+    /// Letters are drawn in upper case, and unknown characters as spaces
+    pub fn text(&mut self, x: usize, y: usize, text: &str, scale: usize, color: Color) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * GLYPH_WIDTH * scale;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        self.fill_rect(left + col * scale, y + row * scale, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Width in pixels of `text` drawn at `scale`
+#[cfg(feature = "display")]
+pub(crate) fn text_width(text: &str, scale: usize) -> usize {
+    text.chars().count() * GLYPH_WIDTH * scale
+}
+
+/// Rows of 3 pixels, top to bottom
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        _ => [0; 5],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = [0, 0, 0];
+    const RED: Color = [255, 0, 0];
+
+    /// Coordinates of pixels set to `color`, row by row
+    fn pixels(canvas: &Canvas, color: Color) -> Vec<(usize, usize)> {
+        (0..canvas.height)
+            .flat_map(|y| (0..canvas.width).map(move |x| (x, y)))
+            .filter(|(x, y)| {
+                let start = (y * canvas.width + x) * 3;
+                canvas.data[start..start + 3] == color
+            })
+            .collect()
+    }
+
+    #[test]
+    fn set_ignores_pixels_outside() {
+        let mut canvas = Canvas::new(2, 2, BLACK);
+        for (x, y) in [(-1, 0), (0, -1), (2, 0), (0, 2), (i64::MAX, 1)] {
+            canvas.set(x, y, RED);
+        }
+        assert!(pixels(&canvas, RED).is_empty());
+        canvas.set(1, 1, RED);
+        assert_eq!(pixels(&canvas, RED), [(1, 1)]);
+    }
+
+    #[test]
+    fn line_includes_both_ends() {
+        let mut canvas = Canvas::new(4, 4, BLACK);
+        canvas.line((0, 0), (3, 3), RED);
+        assert_eq!(pixels(&canvas, RED), [(0, 0), (1, 1), (2, 2), (3, 3)]);
+        let mut canvas = Canvas::new(4, 4, BLACK);
+        canvas.line((2, 1), (2, 1), RED);
+        assert_eq!(pixels(&canvas, RED), [(2, 1)]);
+    }
+
+    #[test]
+    fn line_is_clipped_at_the_edges() {
+        let mut canvas = Canvas::new(3, 2, BLACK);
+        canvas.line((-5, 1), (10, 1), RED);
+        assert_eq!(pixels(&canvas, RED), [(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn fill_rect_is_clipped_at_the_edges() {
+        let mut canvas = Canvas::new(3, 3, BLACK);
+        canvas.fill_rect(2, 1, 5, 5, RED);
+        assert_eq!(pixels(&canvas, RED), [(2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn text_draws_scaled_glyphs() {
+        let mut canvas = Canvas::new(8, 10, BLACK);
+        canvas.text(0, 0, "-", 2, RED);
+        //middle row of the glyph, 3 pixels wide and 1 high, doubled
+        let expected: Vec<_> = (4..6).flat_map(|y| (0..6).map(move |x| (x, y))).collect();
+        assert_eq!(pixels(&canvas, RED), expected);
+        //lower case is drawn as upper case, unknown characters as spaces
+        let mut lower = Canvas::new(8, 5, BLACK);
+        lower.text(0, 0, "a~", 1, RED);
+        let mut upper = Canvas::new(8, 5, BLACK);
+        upper.text(0, 0, "A ", 1, RED);
+        assert_eq!(lower.data, upper.data);
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn frame_is_copied_and_clipped() {
+        let mut canvas = Canvas::new(3, 2, BLACK);
+        canvas.draw_frame(&Frame::gray(2, 2, vec![255; 4]), 2, 1);
+        assert_eq!(pixels(&canvas, [255, 255, 255]), [(2, 1)]);
+        assert_eq!(text_width("ab", 2), 16);
+    }
+
+    #[test]
+    fn into_frame_keeps_pixels() {
+        let mut canvas = Canvas::new(2, 1, BLACK);
+        canvas.set(1, 0, RED);
+        assert_eq!(canvas.into_frame().pixel(1, 0), RED);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use super::{Environment, Frame};
+
+/// Environment where several agents act at the same time e.g. taxis sharing roads.
+/// Train with `Runner::run_independent` or `Runner::run_shared`
+pub trait MultiAgentEnvironment<S, A> {
+    /// Number of agents
+    fn agents(&self) -> usize;
+    /// State as seen by `agent`
+    fn state(&self, agent: usize) -> S;
+    /// Reset the state to starting state
+    fn reset(&mut self, epoch: usize);
+    /// ALL actions, the same for every agent
+    fn all_actions(&self) -> Vec<A>;
+    /// Every agent in `actions` acts at once. Agents which are done are left out.
+    /// Returns the reward of every agent, by index
+    fn take_actions_get_rewards(&mut self, actions: &HashMap<usize, A>) -> Vec<f64>;
+    /// Whether `agent` has finished before the others e.g. dropped off its passenger. It no longer acts this epoch
+    fn is_done(&self, _agent: usize) -> bool {
+        false
+    }
+    /// Should we stop based on state or step count
+    fn should_stop(&mut self, step: usize) -> bool;
+    /// See `Environment::get_image`
+    fn get_image(&mut self) -> Frame<'_> {
+        Frame::default()
+    }
+    /// See `Environment::observation`, as seen by `agent`. Defaults to the image
+    fn observation(&mut self, _agent: usize) -> Vec<f32> {
+        self.get_image().to_tensor()
+    }
+    /// See `Environment::render_text`
+    fn render_text(&self) -> String {
+        String::new()
+    }
+}
+
+/// The environment as seen by one agent, while the others wait, e.g. to create a `DeepQLearning` with the right sizes
+pub struct AgentView<'a, E> {
+    pub environment: &'a mut E,
+    pub agent: usize,
+}
+
+impl<'a, E> AgentView<'a, E> {
+    pub fn new(environment: &'a mut E, agent: usize) -> Self {
+        Self { environment, agent }
+    }
+}
+
+impl<S, A: Clone, E: MultiAgentEnvironment<S, A>> Environment<S, A> for AgentView<'_, E> {
+    fn state(&self) -> S {
+        self.environment.state(self.agent)
+    }
+
+    fn reset(&mut self, epoch: usize) {
+        self.environment.reset(epoch)
+    }
+
+    fn all_actions(&self) -> Vec<A> {
+        self.environment.all_actions()
+    }
+
+    /// Only this agent acts
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let actions = HashMap::from([(self.agent, action.clone())]);
+        let rewards = self.environment.take_actions_get_rewards(&actions);
+        rewards[self.agent]
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        self.environment.should_stop(step) || self.environment.is_done(self.agent)
+    }
+
+    /// This agent has finished
+    fn terminated(&self) -> bool {
+        self.environment.is_done(self.agent)
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.environment.get_image()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.environment.observation(self.agent)
+    }
+
+    fn render_text(&self) -> String {
+        self.environment.render_text()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2 agents, which each get 10 times their index plus their action as a reward,
+    /// and are done after picking 1 twice
+    struct Race {
+        moves: Vec<usize>,
+    }
+
+    impl MultiAgentEnvironment<usize, usize> for Race {
+        fn agents(&self) -> usize {
+            2
+        }
+        fn state(&self, agent: usize) -> usize {
+            self.moves[agent]
+        }
+        fn reset(&mut self, _epoch: usize) {
+            self.moves = vec![0; 2];
+        }
+        fn all_actions(&self) -> Vec<usize> {
+            vec![0, 1]
+        }
+        fn take_actions_get_rewards(&mut self, actions: &HashMap<usize, usize>) -> Vec<f64> {
+            let mut rewards = vec![0.; 2];
+            for (&agent, &action) in actions {
+                self.moves[agent] += action;
+                rewards[agent] = (agent * 10 + action) as f64;
+            }
+            rewards
+        }
+        fn is_done(&self, agent: usize) -> bool {
+            self.moves[agent] >= 2
+        }
+        fn should_stop(&mut self, step: usize) -> bool {
+            step >= 10
+        }
+    }
+
+    #[test]
+    fn view_acts_for_its_agent_only() {
+        let mut env = Race { moves: vec![0; 2] };
+        let mut view = AgentView::new(&mut env, 1);
+        assert_eq!(view.take_action_get_reward(&1), 11.);
+        assert_eq!(view.state(), 1);
+        assert!(!view.terminated());
+        assert!(!view.should_stop(1));
+
+        assert_eq!(view.take_action_get_reward(&1), 11.);
+        assert!(view.terminated());
+        assert!(view.should_stop(2));
+        assert_eq!(env.moves, vec![0, 2]);
+        assert!(!AgentView::new(&mut env, 0).should_stop(2));
+    }
+}
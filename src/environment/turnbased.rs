@@ -0,0 +1,81 @@
+use super::{Environment, Frame};
+
+/// Game where players take turns e.g. board games, so opponents do not have to be faked inside an `Environment`.
+/// Train with `Runner::run_self_play`
+pub trait TurnBasedEnvironment<S, A> {
+    /// State as seen by the current player, so one agent can learn to play from any seat
+    fn state(&self) -> S;
+    /// Reset to the start of a game
+    fn reset(&mut self, epoch: usize);
+    /// Number of players
+    fn players(&self) -> usize {
+        2
+    }
+    /// Whose turn it is, from 0
+    fn current_player(&self) -> usize;
+    /// ALL actions, as for `Environment::all_actions`
+    fn all_actions(&self) -> Vec<A>;
+    /// Actions allowed now, which opponents pick from when moving at random. Defaults to `all_actions`
+    fn valid_actions(&self) -> Vec<A> {
+        self.all_actions()
+    }
+    /// Current player takes `action`. Returns the reward of every player
+    fn take_action_get_rewards(&mut self, action: &A) -> Vec<f64>;
+    /// Whether the game has ended. Games must end on their own
+    fn is_over(&self) -> bool;
+    /// See `Environment::get_image`
+    fn get_image(&mut self) -> Frame<'_> {
+        Frame::default()
+    }
+    /// See `Environment::observation`. Like `state`, as seen by the current player
+    fn observation(&mut self) -> Vec<f32> {
+        self.get_image().to_tensor()
+    }
+    /// See `Environment::render_text`
+    fn render_text(&self) -> String {
+        String::new()
+    }
+}
+
+/// The game as an `Environment` for whoever's turn it is, e.g. to create a `DeepQLearning` with the right sizes.
+/// Rewards are for the player taking the action
+pub struct CurrentPlayer<'a, G>(pub &'a mut G);
+
+impl<S, A, G: TurnBasedEnvironment<S, A>> Environment<S, A> for CurrentPlayer<'_, G> {
+    fn state(&self) -> S {
+        self.0.state()
+    }
+
+    fn reset(&mut self, epoch: usize) {
+        self.0.reset(epoch)
+    }
+
+    fn all_actions(&self) -> Vec<A> {
+        self.0.all_actions()
+    }
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let player = self.0.current_player();
+        self.0.take_action_get_rewards(action)[player]
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        self.0.is_over()
+    }
+
+    fn terminated(&self) -> bool {
+        self.0.is_over()
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.0.get_image()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.0.observation()
+    }
+
+    fn render_text(&self) -> String {
+        self.0.render_text()
+    }
+}
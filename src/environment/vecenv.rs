@@ -0,0 +1,144 @@
+use super::Environment;
+
+/// Several copies of an environment stepped together, so agents can batch their network calls.
+/// Each copy is reset on its own as soon as it ends, with a new epoch number
+pub struct VecEnv<E> {
+    envs: Vec<E>,
+    /// steps taken in the current epoch of each environment
+    steps: Vec<usize>,
+    /// rewards so far in the current epoch of each environment
+    returns: Vec<f64>,
+    /// epochs started over all environments
+    started: usize,
+}
+
+/// Result of stepping every environment once
+pub struct VecStep {
+    pub rewards: Vec<f64>,
+    pub dones: Vec<bool>,
+    /// observation of each environment straight after its action, before it is reset (see `Environment::observation`)
+    pub next_observations: Vec<Vec<f32>>,
+    /// epochs which ended in this step
+    pub finished: Vec<Episode>,
+}
+
+/// A completed epoch of one of the environments
+pub struct Episode {
+    /// index of the environment
+    pub env: usize,
+    pub episode_return: f64,
+    pub length: usize,
+}
+
+impl<E> VecEnv<E> {
+    /// Panics if `envs` is empty
+    pub fn new(envs: Vec<E>) -> Self {
+        assert!(!envs.is_empty(), "need at least 1 environment");
+        let count = envs.len();
+        Self {
+            envs,
+            steps: vec![0; count],
+            returns: vec![0.; count],
+            started: 0,
+        }
+    }
+
+    /// Creates `count` environments with `f`
+    pub fn from_fn(count: usize, f: impl FnMut() -> E) -> Self {
+        Self::new(std::iter::repeat_with(f).take(count).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn envs(&self) -> &[E] {
+        &self.envs
+    }
+
+    pub fn envs_mut(&mut self) -> &mut [E] {
+        &mut self.envs
+    }
+
+    /// epochs started over all environments
+    pub fn epochs_started(&self) -> usize {
+        self.started
+    }
+
+    /// Resets all environments, each with a new epoch number
+    pub fn reset<S, A>(&mut self)
+    where
+        E: Environment<S, A>,
+    {
+        for i in 0..self.envs.len() {
+            self.reset_one(i);
+        }
+    }
+
+    fn reset_one<S, A>(&mut self, i: usize)
+    where
+        E: Environment<S, A>,
+    {
+        self.started += 1;
+        self.envs[i].reset(self.started);
+        self.steps[i] = 0;
+        self.returns[i] = 0.;
+    }
+
+    pub fn states<S, A>(&self) -> Vec<S>
+    where
+        E: Environment<S, A>,
+    {
+        self.envs.iter().map(|e| e.state()).collect()
+    }
+
+    /// Observation of each environment as used by deep learning (see `Environment::observation`)
+    pub fn observations<S, A>(&mut self) -> Vec<Vec<f32>>
+    where
+        E: Environment<S, A>,
+    {
+        self.envs.iter_mut().map(|e| e.observation()).collect()
+    }
+
+    /// Takes one action in each environment, then resets any that should stop.
+    /// Panics if there is not 1 action per environment
+    pub fn step<S, A>(&mut self, actions: &[A]) -> VecStep
+    where
+        E: Environment<S, A>,
+    {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "need 1 action per environment"
+        );
+        let mut result = VecStep {
+            rewards: Vec::with_capacity(actions.len()),
+            dones: Vec::with_capacity(actions.len()),
+            next_observations: Vec::with_capacity(actions.len()),
+            finished: vec![],
+        };
+        for (i, action) in actions.iter().enumerate() {
+            let env = &mut self.envs[i];
+            let reward = env.take_action_get_reward(action);
+            self.steps[i] += 1;
+            self.returns[i] += reward;
+            let done = env.should_stop(self.steps[i]);
+            result.next_observations.push(env.observation());
+            result.rewards.push(reward);
+            result.dones.push(done);
+            if done {
+                result.finished.push(Episode {
+                    env: i,
+                    episode_return: self.returns[i],
+                    length: self.steps[i],
+                });
+                self.reset_one(i);
+            }
+        }
+        result
+    }
+}
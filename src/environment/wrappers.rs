@@ -0,0 +1,668 @@
+//! Wrappers which change one thing about any environment, and can be stacked e.g.
+//! `TimeLimit::new(RewardClip::new(ActionRepeat::new(env, 4), -1., 1.), 500)`.
+//! The state is passed through unchanged, and observation wrappers only change `Environment::observation`
+
+use std::collections::VecDeque;
+
+use super::{Environment, Frame};
+
+/// Passes the listed `Environment` methods through to `self.environment`
+macro_rules! forward {
+    (state) => {
+        fn state(&self) -> S {
+            self.environment.state()
+        }
+    };
+    (reset) => {
+        fn reset(&mut self, epoch: usize) {
+            self.environment.reset(epoch)
+        }
+    };
+    (all_actions) => {
+        fn all_actions(&self) -> Vec<A> {
+            self.environment.all_actions()
+        }
+    };
+    (take_action_get_reward) => {
+        fn take_action_get_reward(&mut self, action: &A) -> f64 {
+            self.environment.take_action_get_reward(action)
+        }
+    };
+    (should_stop) => {
+        fn should_stop(&mut self, step: usize) -> bool {
+            self.environment.should_stop(step)
+        }
+    };
+    (terminated) => {
+        fn terminated(&self) -> bool {
+            self.environment.terminated()
+        }
+    };
+    (unshaped_reward) => {
+        fn unshaped_reward(&self) -> Option<f64> {
+            self.environment.unshaped_reward()
+        }
+    };
+    (regret) => {
+        fn regret(&self) -> Option<f64> {
+            self.environment.regret()
+        }
+    };
+    (get_image) => {
+        fn get_image(&mut self) -> Frame<'_> {
+            self.environment.get_image()
+        }
+    };
+    (observation) => {
+        fn observation(&mut self) -> Vec<f32> {
+            self.environment.observation()
+        }
+    };
+    (render_text) => {
+        fn render_text(&self) -> String {
+            self.environment.render_text()
+        }
+    };
+    ($($method:ident),+) => {
+        $(forward!($method);)+
+    };
+}
+
+/// Access to the wrapped environment e.g. to read its results after running
+macro_rules! inner {
+    ($wrapper:ident $(, $param:ident)*) => {
+        impl<E $(, $param)*> $wrapper<E $(, $param)*> {
+            pub fn inner(&self) -> &E {
+                &self.environment
+            }
+
+            pub fn inner_mut(&mut self) -> &mut E {
+                &mut self.environment
+            }
+
+            pub fn into_inner(self) -> E {
+                self.environment
+            }
+        }
+    };
+}
+
+/// Stops after `max_steps`, or when the environment stops
+#[derive(Clone)]
+pub struct TimeLimit<E> {
+    environment: E,
+    max_steps: usize,
+}
+
+impl<E> TimeLimit<E> {
+    pub fn new(environment: E, max_steps: usize) -> Self {
+        Self {
+            environment,
+            max_steps,
+        }
+    }
+}
+
+inner!(TimeLimit);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for TimeLimit<E> {
+    forward!(state, reset, all_actions, take_action_get_reward);
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        let stop = self.environment.should_stop(step);
+        stop || step >= self.max_steps
+    }
+
+    forward!(
+        terminated,
+        unshaped_reward,
+        regret,
+        get_image,
+        observation,
+        render_text
+    );
+}
+
+/// Multiplies rewards by `scale` e.g. to keep deep learning targets small
+#[derive(Clone)]
+pub struct RewardScale<E> {
+    environment: E,
+    scale: f64,
+}
+
+impl<E> RewardScale<E> {
+    pub fn new(environment: E, scale: f64) -> Self {
+        Self { environment, scale }
+    }
+}
+
+inner!(RewardScale);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for RewardScale<E> {
+    forward!(state, reset, all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        self.environment.take_action_get_reward(action) * self.scale
+    }
+
+    forward!(
+        should_stop,
+        terminated,
+        unshaped_reward,
+        regret,
+        get_image,
+        observation,
+        render_text
+    );
+}
+
+/// Keeps rewards between `min` and `max`
+#[derive(Clone)]
+pub struct RewardClip<E> {
+    environment: E,
+    min: f64,
+    max: f64,
+}
+
+impl<E> RewardClip<E> {
+    /// Panics if `min` is more than `max`, or either is NaN
+    pub fn new(environment: E, min: f64, max: f64) -> Self {
+        assert!(min <= max, "min {min} must be at most max {max}");
+        Self {
+            environment,
+            min,
+            max,
+        }
+    }
+}
+
+inner!(RewardClip);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for RewardClip<E> {
+    forward!(state, reset, all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        self.environment
+            .take_action_get_reward(action)
+            .clamp(self.min, self.max)
+    }
+
+    forward!(
+        should_stop,
+        terminated,
+        unshaped_reward,
+        regret,
+        get_image,
+        observation,
+        render_text
+    );
+}
+
+/// Takes each action `repeat` times, summing the rewards, so agents decide less often (frame skipping).
+/// Stops repeating as soon as the environment stops. The environment's `should_stop` gets its own step count
+#[derive(Clone)]
+pub struct ActionRepeat<E> {
+    environment: E,
+    repeat: usize,
+    /// steps taken by the environment this epoch
+    steps: usize,
+    done: bool,
+    /// sum of the environment's unshaped rewards for the last action
+    unshaped_reward: Option<f64>,
+}
+
+impl<E> ActionRepeat<E> {
+    pub fn new(environment: E, repeat: usize) -> Self {
+        Self {
+            environment,
+            repeat: repeat.max(1),
+            steps: 0,
+            done: false,
+            unshaped_reward: None,
+        }
+    }
+}
+
+inner!(ActionRepeat);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for ActionRepeat<E> {
+    forward!(state);
+
+    fn reset(&mut self, epoch: usize) {
+        self.steps = 0;
+        self.done = false;
+        self.unshaped_reward = None;
+        self.environment.reset(epoch)
+    }
+
+    forward!(all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let mut reward = 0.;
+        self.unshaped_reward = None;
+        for _ in 0..self.repeat {
+            reward += self.environment.take_action_get_reward(action);
+            if let Some(raw) = self.environment.unshaped_reward() {
+                *self.unshaped_reward.get_or_insert(0.) += raw;
+            }
+            self.steps += 1;
+            self.done = self.environment.should_stop(self.steps);
+            if self.done {
+                break;
+            }
+        }
+        reward
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        self.done
+    }
+
+    forward!(terminated);
+
+    fn unshaped_reward(&self) -> Option<f64> {
+        self.unshaped_reward
+    }
+
+    forward!(regret, get_image, observation, render_text);
+}
+
+/// Observation is the last `frames` observations joined together, oldest first, so deep agents can see movement.
+/// At the start of an epoch the first observation is repeated.
+/// One observation is stacked on reset and per step, when `should_stop` or `observation` is first called after the action,
+/// so none are missed when an agent does not look at every step.
+/// Only the observation is stacked, `get_image` is the environment's latest image
+#[derive(Clone)]
+pub struct FrameStack<E> {
+    environment: E,
+    frames: usize,
+    stack: VecDeque<Vec<f32>>,
+    /// whether the environment has changed since the last observation was stacked
+    changed: bool,
+}
+
+impl<E> FrameStack<E> {
+    pub fn new(environment: E, frames: usize) -> Self {
+        Self {
+            environment,
+            frames: frames.max(1),
+            stack: VecDeque::new(),
+            changed: true,
+        }
+    }
+}
+
+inner!(FrameStack);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for FrameStack<E> {
+    forward!(state);
+
+    fn reset(&mut self, epoch: usize) {
+        self.stack.clear();
+        self.changed = true;
+        self.environment.reset(epoch);
+        self.push();
+    }
+
+    forward!(all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        self.changed = true;
+        self.environment.take_action_get_reward(action)
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        let stop = self.environment.should_stop(step);
+        self.push();
+        stop
+    }
+
+    forward!(terminated, unshaped_reward, regret, get_image);
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.push();
+        self.stack.iter().flatten().copied().collect()
+    }
+
+    forward!(render_text);
+}
+
+impl<E> FrameStack<E> {
+    /// Stacks the environment's observation if it has changed since the last one
+    fn push<S, A>(&mut self)
+    where
+        E: Environment<S, A>,
+    {
+        if self.changed {
+            let observation = self.environment.observation();
+            while self.stack.len() < self.frames {
+                self.stack.push_back(observation.clone());
+            }
+            self.stack.push_back(observation);
+            self.stack.pop_front();
+            self.changed = false;
+        }
+    }
+}
+
+/// Observation has each value scaled by a running mean and standard deviation of every observation seen,
+/// so deep agents get inputs around 0 without knowing their range
+#[derive(Clone)]
+pub struct ObservationNormalize<E> {
+    environment: E,
+    clip: f32,
+    update: bool,
+    count: f64,
+    mean: Vec<f64>,
+    /// sum of squared differences from the mean
+    squares: Vec<f64>,
+    /// normalized latest observation
+    latest: Vec<f32>,
+    changed: bool,
+}
+
+impl<E> ObservationNormalize<E> {
+    pub fn new(environment: E) -> Self {
+        Self {
+            environment,
+            clip: 10.,
+            update: true,
+            count: 0.,
+            mean: vec![],
+            squares: vec![],
+            latest: vec![],
+            changed: true,
+        }
+    }
+
+    /// Normalized values are kept between -`clip` and `clip`. Default is 10
+    pub fn with_clip(mut self, clip: f32) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    /// Whether new observations change the mean and standard deviation. Turn off to evaluate
+    pub fn set_update(&mut self, update: bool) {
+        self.update = update;
+    }
+
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    pub fn std(&self) -> Vec<f64> {
+        self.squares
+            .iter()
+            .map(|s| (s / self.count.max(1.)).sqrt())
+            .collect()
+    }
+
+    /// Welford's online algorithm
+    fn add(&mut self, observation: &[f32]) {
+        if self.mean.len() != observation.len() {
+            self.count = 0.;
+            self.mean = vec![0.; observation.len()];
+            self.squares = vec![0.; observation.len()];
+        }
+        self.count += 1.;
+        for ((mean, squares), &x) in self.mean.iter_mut().zip(&mut self.squares).zip(observation) {
+            let x = x as f64;
+            let delta = x - *mean;
+            *mean += delta / self.count;
+            *squares += delta * (x - *mean);
+        }
+    }
+}
+
+inner!(ObservationNormalize);
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for ObservationNormalize<E> {
+    forward!(state);
+
+    fn reset(&mut self, epoch: usize) {
+        self.changed = true;
+        self.environment.reset(epoch)
+    }
+
+    forward!(all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        self.changed = true;
+        self.environment.take_action_get_reward(action)
+    }
+
+    forward!(should_stop, terminated, unshaped_reward, regret, get_image);
+
+    fn observation(&mut self) -> Vec<f32> {
+        if self.changed {
+            let observation = self.environment.observation();
+            if self.update || self.mean.len() != observation.len() {
+                self.add(&observation);
+            }
+            let std = self.std();
+            self.latest = observation
+                .iter()
+                .zip(&self.mean)
+                .zip(std)
+                .map(|((&x, mean), std)| {
+                    let normalized = (x as f64 - mean) / (std + 1e-8);
+                    (normalized as f32).clamp(-self.clip, self.clip)
+                })
+                .collect();
+            self.changed = false;
+        }
+        self.latest.clone()
+    }
+
+    forward!(render_text);
+}
+
+/// Adds `discount * potential(next state) - potential(state)` to every reward, which guides learning towards
+/// states with higher potential without changing the best policy (Ng et al. 1999).
+/// `discount` should be the agent's. The potential of a terminal state (`Environment::terminated`) is taken as 0,
+/// as the policy is only unchanged if it is. Epochs cut short e.g. by `TimeLimit` keep the next state's potential,
+/// as learning bootstraps from that state. The environment's `should_stop` is called after every action
+/// with its own step count, so it can stop and terminate as usual.
+/// Rewards before shaping are recorded by the runners in `Metrics::raw_returns`
+#[derive(Clone)]
+pub struct PotentialShaping<E, F> {
+    environment: E,
+    discount: f64,
+    potential: F,
+    /// potential of the current state
+    current: f64,
+    /// last reward before shaping
+    reward: f64,
+    /// steps taken by the environment this epoch
+    steps: usize,
+    done: bool,
+}
+
+impl<E, F> PotentialShaping<E, F> {
+    pub fn new(environment: E, discount: f64, potential: F) -> Self {
+        Self {
+            environment,
+            discount,
+            potential,
+            current: 0.,
+            reward: 0.,
+            steps: 0,
+            done: false,
+        }
+    }
+}
+
+inner!(PotentialShaping, F);
+
+impl<S, A, E: Environment<S, A>, F: Fn(&S) -> f64> Environment<S, A> for PotentialShaping<E, F> {
+    forward!(state);
+
+    fn reset(&mut self, epoch: usize) {
+        self.reward = 0.;
+        self.steps = 0;
+        self.done = false;
+        self.environment.reset(epoch);
+        self.current = (self.potential)(&self.environment.state());
+    }
+
+    forward!(all_actions);
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let reward = self.environment.take_action_get_reward(action);
+        self.reward = reward;
+        self.steps += 1;
+        self.done = self.environment.should_stop(self.steps);
+        let next = match self.environment.terminated() {
+            true => 0.,
+            false => (self.potential)(&self.environment.state()),
+        };
+        let shaped = reward + self.discount * next - self.current;
+        self.current = next;
+        shaped
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        self.done
+    }
+
+    forward!(terminated);
+
+    /// The innermost reward before shaping, if shaping wrappers are stacked
+    fn unshaped_reward(&self) -> Option<f64> {
+        Some(self.environment.unshaped_reward().unwrap_or(self.reward))
+    }
+
+    forward!(regret, get_image, observation, render_text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::qlearning::QLearning, runner::Runner, strategy::decliningrandom::DecliningRandom,
+    };
+
+    /// Observation is the number of actions taken this epoch
+    struct Counter(usize);
+
+    impl Environment<usize, ()> for Counter {
+        fn state(&self) -> usize {
+            self.0
+        }
+        fn reset(&mut self, _epoch: usize) {
+            self.0 = 0;
+        }
+        fn all_actions(&self) -> Vec<()> {
+            vec![()]
+        }
+        fn take_action_get_reward(&mut self, _action: &()) -> f64 {
+            self.0 += 1;
+            1.
+        }
+        fn should_stop(&mut self, _step: usize) -> bool {
+            false
+        }
+        fn observation(&mut self) -> Vec<f32> {
+            vec![self.0 as f32]
+        }
+    }
+
+    #[test]
+    fn frame_stack_keeps_steps_which_were_not_observed() {
+        let mut env = FrameStack::new(Counter(0), 4);
+        env.reset(1);
+        assert_eq!(env.observation(), vec![0.; 4]);
+        for step in 1..=2 {
+            env.take_action_get_reward(&());
+            env.should_stop(step);
+        }
+        assert_eq!(env.observation(), vec![0., 0., 1., 2.]);
+        env.take_action_get_reward(&());
+        assert_eq!(env.observation(), vec![0., 1., 2., 3.]);
+        env.should_stop(3);
+        assert_eq!(env.observation(), vec![0., 1., 2., 3.]);
+
+        env.reset(2);
+        env.take_action_get_reward(&());
+        env.should_stop(1);
+        assert_eq!(env.observation(), vec![0., 0., 0., 1.]);
+    }
+
+    /// Counts actions like `Counter`, and reaches a terminal state after 3
+    struct Goal(usize);
+
+    impl Environment<usize, ()> for Goal {
+        fn state(&self) -> usize {
+            self.0
+        }
+        fn reset(&mut self, _epoch: usize) {
+            self.0 = 0;
+        }
+        fn all_actions(&self) -> Vec<()> {
+            vec![()]
+        }
+        fn take_action_get_reward(&mut self, _action: &()) -> f64 {
+            self.0 += 1;
+            1.
+        }
+        fn should_stop(&mut self, _step: usize) -> bool {
+            self.terminated()
+        }
+        fn terminated(&self) -> bool {
+            self.0 >= 3
+        }
+    }
+
+    /// Rewards of 3 steps, checking it only stops after the last
+    fn shaped_rewards(env: &mut impl Environment<usize, ()>) -> Vec<f64> {
+        env.reset(1);
+        (1..=3)
+            .map(|step| {
+                let reward = env.take_action_get_reward(&());
+                assert_eq!(env.should_stop(step), step == 3);
+                reward
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shaping_keeps_potential_when_cut_short() {
+        let potential = |state: &usize| *state as f64 * 10. + 5.;
+        let mut env = PotentialShaping::new(TimeLimit::new(Counter(0), 3), 1., potential);
+        //the last state's potential of 35 is added, as the epoch did not end by itself
+        assert_eq!(shaped_rewards(&mut env), vec![11., 11., 11.]);
+    }
+
+    #[test]
+    fn shaping_ends_with_no_potential() {
+        let potential = |state: &usize| *state as f64 * 10. + 5.;
+        let mut env = PotentialShaping::new(Goal(0), 1., potential);
+        //the terminal state's potential of 35 is not added, so the returns only differ by the first potential
+        assert_eq!(shaped_rewards(&mut env), vec![11., 11., -24.]);
+    }
+
+    #[test]
+    fn runner_records_unshaped_returns() {
+        let potential = |state: &usize| *state as f64 * 10. + 5.;
+        let env = ActionRepeat::new(
+            PotentialShaping::new(TimeLimit::new(Counter(0), 3), 1., potential),
+            2,
+        );
+        let agent = QLearning::new(0.1, 1., DecliningRandom::new(4, 0.));
+        let (_, metrics) = Runner::run_with_metrics(agent, env, 4);
+        assert_eq!(metrics.episode_returns, vec![33.; 4]);
+        assert_eq!(metrics.raw_returns, vec![3.; 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reward_clip_needs_min_below_max() {
+        RewardClip::new(Counter(0), 1., -1.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reward_clip_needs_bounds() {
+        RewardClip::new(Counter(0), f64::NAN, 1.);
+    }
+}
@@ -0,0 +1,79 @@
+//! Reference environments with well known results, for benchmarking agents.
+//! Classic control state is discretized into bins for `QLearning`, and `Environment::observation` returns the raw values for deep agents.
+//! None of them print, so progress comes from `Metrics`
+
+pub mod acrobot;
+pub mod bandit;
+mod board;
+pub mod cartpole;
+pub mod connectfour;
+pub mod gridworld;
+pub mod hike;
+pub mod mountaincar;
+pub mod mouse;
+pub mod multitaxi;
+pub mod taxi;
+pub mod tictactoe;
+
+/// Cell in a grid, with `y` down from the top row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Straight line distance
+    pub fn distance(&self, other: &Point) -> f64 {
+        let dx = self.x as f64 - other.x as f64;
+        let dy = self.y as f64 - other.y as f64;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+/// Cell of a board game, as seen by the player whose turn it is
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mark {
+    #[default]
+    Empty,
+    Mine,
+    Theirs,
+}
+
+/// Rewards for board games, given to each player when the game ends
+#[derive(Clone)]
+pub struct GameRewards {
+    pub win: f64,
+    pub loss: f64,
+    pub draw: f64,
+    /// for the player making an invalid move, which ends the game. Other players get 0
+    pub invalid: f64,
+}
+
+impl Default for GameRewards {
+    fn default() -> Self {
+        Self {
+            win: 1.,
+            loss: -1.,
+            draw: 0.,
+            invalid: -1.,
+        }
+    }
+}
+
+/// Which of `bins` equal parts between `min` and `max` `value` is in. Values outside go in the first or last bin
+fn bin(value: f64, min: f64, max: f64, bins: usize) -> usize {
+    let fraction = (value - min) / (max - min);
+    ((fraction * bins as f64).floor().max(0.) as usize).min(bins.saturating_sub(1))
+}
+
+/// Uniform random value between `min` and `max`
+fn uniform(min: f64, max: f64) -> f64 {
+    min + (max - min) * fastrand::f64()
+}
@@ -0,0 +1,295 @@
+// Acrobot
+// Two links hang from a pivot, and torque on the joint between them swings the end above the pivot's height,
+// as in gymnasium Acrobot-v1.
+// Reward is -1 per step until the end is 1 link length above the pivot, or after `max_steps`
+
+use std::f64::consts::PI;
+
+use crate::environment::{draw::Canvas, Environment, Frame};
+
+use super::{bin, uniform};
+
+/// seconds between steps
+const DT: f64 = 0.2;
+const LINK_LENGTH_1: f64 = 1.;
+const LINK_MASS_1: f64 = 1.;
+const LINK_MASS_2: f64 = 1.;
+/// position of the centre of mass of each link
+const LINK_COM_1: f64 = 0.5;
+const LINK_COM_2: f64 = 0.5;
+/// moment of inertia of each link
+const LINK_MOI: f64 = 1.;
+const GRAVITY: f64 = 9.8;
+const MAX_VEL_1: f64 = 4. * PI;
+const MAX_VEL_2: f64 = 9. * PI;
+/// range of each value used for bins
+const BOUNDS: [(f64, f64); 4] = [
+    (-PI, PI),
+    (-PI, PI),
+    (-MAX_VEL_1, MAX_VEL_1),
+    (-MAX_VEL_2, MAX_VEL_2),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AcrobotAction {
+    /// torque of -1
+    Negative,
+    Zero,
+    /// torque of 1
+    Positive,
+}
+
+pub struct AcrobotConfig {
+    /// 500 as in Acrobot-v1
+    pub max_steps: usize,
+    /// bins for each of the 2 angles and 2 angular velocities in `state`
+    pub bins: usize,
+}
+
+impl Default for AcrobotConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 500,
+            bins: 6,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Acrobot {
+    max_steps: usize,
+    bins: usize,
+    /// angle of the first link from hanging down, angle of the second link relative to the first,
+    /// and their angular velocities
+    values: [f64; 4],
+}
+
+impl Default for Acrobot {
+    fn default() -> Self {
+        Self::new(AcrobotConfig::default())
+    }
+}
+
+impl Acrobot {
+    pub fn new(config: AcrobotConfig) -> Self {
+        Self {
+            max_steps: config.max_steps,
+            bins: config.bins.max(1),
+            values: [0.; 4],
+        }
+    }
+
+    /// angle of each link and their angular velocities
+    pub fn values(&self) -> [f64; 4] {
+        self.values
+    }
+}
+
+/// Derivative of `s` with `torque` on the joint, using the equations from Sutton and Barto's book
+fn derivative(s: [f64; 4], torque: f64) -> [f64; 4] {
+    let [theta1, theta2, dtheta1, dtheta2] = s;
+    let (m1, m2, l1, lc1, lc2) = (
+        LINK_MASS_1,
+        LINK_MASS_2,
+        LINK_LENGTH_1,
+        LINK_COM_1,
+        LINK_COM_2,
+    );
+    let d1 = m1 * lc1 * lc1
+        + m2 * (l1 * l1 + lc2 * lc2 + 2. * l1 * lc2 * theta2.cos())
+        + LINK_MOI
+        + LINK_MOI;
+    let d2 = m2 * (lc2 * lc2 + l1 * lc2 * theta2.cos()) + LINK_MOI;
+    let phi2 = m2 * lc2 * GRAVITY * (theta1 + theta2 - PI / 2.).cos();
+    let phi1 = -m2 * l1 * lc2 * dtheta2 * dtheta2 * theta2.sin()
+        - 2. * m2 * l1 * lc2 * dtheta2 * dtheta1 * theta2.sin()
+        + (m1 * lc1 + m2 * l1) * GRAVITY * (theta1 - PI / 2.).cos()
+        + phi2;
+    let ddtheta2 =
+        (torque + d2 / d1 * phi1 - m2 * l1 * lc2 * dtheta1 * dtheta1 * theta2.sin() - phi2)
+            / (m2 * lc2 * lc2 + LINK_MOI - d2 * d2 / d1);
+    let ddtheta1 = -(d2 * ddtheta2 + phi1) / d1;
+    [dtheta1, dtheta2, ddtheta1, ddtheta2]
+}
+
+/// One runge kutta step of `DT`
+fn rk4(s: [f64; 4], torque: f64) -> [f64; 4] {
+    let add =
+        |s: [f64; 4], k: [f64; 4], h: f64| -> [f64; 4] { std::array::from_fn(|i| s[i] + h * k[i]) };
+    let k1 = derivative(s, torque);
+    let k2 = derivative(add(s, k1, DT / 2.), torque);
+    let k3 = derivative(add(s, k2, DT / 2.), torque);
+    let k4 = derivative(add(s, k3, DT), torque);
+    std::array::from_fn(|i| s[i] + DT / 6. * (k1[i] + 2. * k2[i] + 2. * k3[i] + k4[i]))
+}
+
+/// `angle` between -PI and PI
+fn wrap(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(2. * PI) - PI
+}
+
+impl Environment<[usize; 4], AcrobotAction> for Acrobot {
+    /// bin of each value
+    fn state(&self) -> [usize; 4] {
+        std::array::from_fn(|i| bin(self.values[i], BOUNDS[i].0, BOUNDS[i].1, self.bins))
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.values = std::array::from_fn(|_| uniform(-0.1, 0.1));
+    }
+
+    fn all_actions(&self) -> Vec<AcrobotAction> {
+        vec![
+            AcrobotAction::Negative,
+            AcrobotAction::Zero,
+            AcrobotAction::Positive,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &AcrobotAction) -> f64 {
+        let torque = match action {
+            AcrobotAction::Negative => -1.,
+            AcrobotAction::Zero => 0.,
+            AcrobotAction::Positive => 1.,
+        };
+        let [theta1, theta2, dtheta1, dtheta2] = rk4(self.values, torque);
+        self.values = [
+            wrap(theta1),
+            wrap(theta2),
+            dtheta1.clamp(-MAX_VEL_1, MAX_VEL_1),
+            dtheta2.clamp(-MAX_VEL_2, MAX_VEL_2),
+        ];
+        if self.terminated() {
+            0.
+        } else {
+            -1.
+        }
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        self.terminated() || step >= self.max_steps
+    }
+
+    fn terminated(&self) -> bool {
+        let [theta1, theta2, ..] = self.values;
+        -theta1.cos() - (theta1 + theta2).cos() > 1.
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        let size = 100;
+        let scale = size as f64 / 5.;
+        let centre = (size / 2) as i64;
+        let [theta1, theta2, ..] = self.values;
+        //hanging down when angles are 0
+        let point = |x: f64, y: f64| (centre + (x * scale) as i64, centre + (y * scale) as i64);
+        let (x1, y1) = (LINK_LENGTH_1 * theta1.sin(), LINK_LENGTH_1 * theta1.cos());
+        let (x2, y2) = (x1 + (theta1 + theta2).sin(), y1 + (theta1 + theta2).cos());
+        let mut canvas = Canvas::new(size, size, [255, 255, 255]);
+        let goal = centre - scale as i64;
+        canvas.line((0, goal), (size as i64, goal), [0, 0, 0]);
+        canvas.line(point(0., 0.), point(x1, y1), [0, 204, 204]);
+        canvas.line(point(x1, y1), point(x2, y2), [0, 204, 204]);
+        canvas.fill_rect(
+            centre as usize - 1,
+            centre as usize - 1,
+            3,
+            3,
+            [204, 204, 0],
+        );
+        canvas.into_frame()
+    }
+
+    /// cos and sin of each angle, and the angular velocities, as in Acrobot-v1
+    fn observation(&mut self) -> Vec<f32> {
+        let [theta1, theta2, dtheta1, dtheta2] = self.values;
+        [
+            theta1.cos(),
+            theta1.sin(),
+            theta2.cos(),
+            theta2.sin(),
+            dtheta1,
+            dtheta2,
+        ]
+        .iter()
+        .map(|x| *x as f32)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: [f64; 4], b: [f64; 4]) {
+        assert!(
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn step_matches_gymnasium() {
+        //from the Acrobot-v1 step equations, with the same starting values
+        let mut env = Acrobot {
+            values: [0.05, -0.03, 0.02, 0.01],
+            ..Default::default()
+        };
+        assert_eq!(env.take_action_get_reward(&AcrobotAction::Positive), -1.);
+        assert_close(
+            env.values(),
+            [
+                0.033964268077,
+                0.014028356137,
+                -0.175464650252,
+                0.419715602265,
+            ],
+        );
+        env.values = [1., 0.5, -2., 3.];
+        env.take_action_get_reward(&AcrobotAction::Negative);
+        assert_close(
+            env.values(),
+            [
+                0.528773280010,
+                1.072035374901,
+                -2.644882255333,
+                2.562820345976,
+            ],
+        );
+    }
+
+    #[test]
+    fn angles_wrap_and_velocities_are_bounded() {
+        assert!((wrap(PI + 0.5) - (-PI + 0.5)).abs() < 1e-12);
+        assert!((wrap(-PI - 0.5) - (PI - 0.5)).abs() < 1e-12);
+        let mut env = Acrobot {
+            values: [0., 0., 100., -100.],
+            ..Default::default()
+        };
+        env.take_action_get_reward(&AcrobotAction::Zero);
+        let [theta1, theta2, dtheta1, dtheta2] = env.values();
+        assert!((-PI..PI).contains(&theta1) && (-PI..PI).contains(&theta2));
+        assert_eq!((dtheta1.abs(), dtheta2.abs()), (MAX_VEL_1, MAX_VEL_2));
+    }
+
+    #[test]
+    fn terminates_above_one_link_length() {
+        let mut env = Acrobot::default();
+        for (values, terminated) in [
+            //hanging down
+            ([0., 0., 0., 0.], false),
+            //first link level, second pointing up, so the end is exactly 1 above
+            ([PI / 2., PI / 2. - 1e-6, 0., 0.], false),
+            //straight up
+            ([PI, 0., 0., 0.], true),
+            ([PI / 2. + 1e-3, PI / 2. - 1e-3, 0., 0.], true),
+        ] {
+            env.values = values;
+            assert_eq!(env.terminated(), terminated, "{values:?}");
+            assert_eq!(env.should_stop(1), terminated);
+        }
+        //no penalty for the step which reaches the goal
+        env.values = [PI, 0., 0., 0.];
+        assert_eq!(env.take_action_get_reward(&AcrobotAction::Zero), 0.);
+    }
+}
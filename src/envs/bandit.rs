@@ -0,0 +1,301 @@
+// Bandits
+// Pick 1 of k arms, and get a random reward from it. Every epoch is a single pull, so the agent only has to
+// learn which arm is best, while exploring as little as possible.
+// The environment knows the expected reward of every arm, so it keeps the cumulative regret:
+// how much more we would have got on average by always pulling the best arm.
+// It is returned by `Environment::regret`, so runners record it in `Metrics::regrets` after every epoch
+//
+// `Bandit` has the same arms every epoch, and `ContextualBandit` shows features first, which change
+// which arm is best e.g. a user's details when picking which page to show them
+
+use crate::environment::Environment;
+use crate::random::normal;
+
+/// Rewards from one arm
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arm {
+    /// 1 with chance `p`, otherwise 0
+    Bernoulli(f64),
+    Gaussian {
+        mean: f64,
+        std: f64,
+    },
+}
+
+impl Arm {
+    /// expected reward
+    pub fn mean(&self) -> f64 {
+        match self {
+            Arm::Bernoulli(p) => *p,
+            Arm::Gaussian { mean, .. } => *mean,
+        }
+    }
+
+    fn pull(&self) -> f64 {
+        match self {
+            Arm::Bernoulli(p) => (fastrand::f64() < *p) as u8 as f64,
+            Arm::Gaussian { mean, std } => normal(*mean, *std),
+        }
+    }
+
+    /// Random walk of the mean, keeping bernoulli chances between 0 and 1
+    fn drift(&mut self, std: f64) {
+        match self {
+            Arm::Bernoulli(p) => *p = (*p + normal(0., std)).clamp(0., 1.),
+            Arm::Gaussian { mean, .. } => *mean += normal(0., std),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BanditConfig {
+    pub arms: Vec<Arm>,
+    /// standard deviation of the change in every arm's mean after each pull. 0 for stationary arms
+    pub drift: f64,
+}
+
+impl Default for BanditConfig {
+    /// 10 armed testbed from Sutton and Barto, with means picked from a standard normal
+    fn default() -> Self {
+        Self {
+            arms: (0..10)
+                .map(|_| Arm::Gaussian {
+                    mean: normal(0., 1.),
+                    std: 1.,
+                })
+                .collect(),
+            drift: 0.,
+        }
+    }
+}
+
+/// k armed bandit. The state is always `()`, and actions are the index of the arm
+#[derive(Clone)]
+pub struct Bandit {
+    arms: Vec<Arm>,
+    drift: f64,
+    regret: f64,
+}
+
+impl Default for Bandit {
+    fn default() -> Self {
+        Self::new(BanditConfig::default())
+    }
+}
+
+impl Bandit {
+    /// Panics if there are no arms
+    pub fn new(config: BanditConfig) -> Self {
+        assert!(!config.arms.is_empty(), "bandit needs at least 1 arm");
+        Self {
+            arms: config.arms,
+            drift: config.drift,
+            regret: 0.,
+        }
+    }
+
+    /// Arms paying 1 with each chance in `chances`
+    pub fn bernoulli(chances: &[f64]) -> Self {
+        Self::new(BanditConfig {
+            arms: chances.iter().map(|&p| Arm::Bernoulli(p)).collect(),
+            drift: 0.,
+        })
+    }
+
+    /// Arms with each mean in `means`, and standard deviation 1
+    pub fn gaussian(means: &[f64]) -> Self {
+        Self::new(BanditConfig {
+            arms: means
+                .iter()
+                .map(|&mean| Arm::Gaussian { mean, std: 1. })
+                .collect(),
+            drift: 0.,
+        })
+    }
+
+    pub fn arms(&self) -> &[Arm] {
+        &self.arms
+    }
+
+    /// Index of the arm with the highest mean right now
+    pub fn best_arm(&self) -> usize {
+        best(self.arms.iter().map(Arm::mean))
+    }
+}
+
+impl Environment<(), usize> for Bandit {
+    fn state(&self) {}
+
+    /// Arms are not reset, so they keep drifting
+    fn reset(&mut self, _epoch: usize) {}
+
+    fn all_actions(&self) -> Vec<usize> {
+        (0..self.arms.len()).collect()
+    }
+
+    fn take_action_get_reward(&mut self, action: &usize) -> f64 {
+        let reward = self.arms[*action].pull();
+        self.regret += self.arms[self.best_arm()].mean() - self.arms[*action].mean();
+        if self.drift > 0. {
+            let drift = self.drift;
+            self.arms.iter_mut().for_each(|arm| arm.drift(drift));
+        }
+        reward
+    }
+
+    /// 1 pull per epoch
+    fn should_stop(&mut self, _step: usize) -> bool {
+        true
+    }
+
+    /// Total expected reward lost by not pulling the best arm
+    fn regret(&self) -> Option<f64> {
+        Some(self.regret)
+    }
+}
+
+#[derive(Clone)]
+pub struct ContextualBanditConfig {
+    /// weights of each arm. The mean reward of an arm is its weights multiplied by the features
+    pub weights: Vec<Vec<f64>>,
+    /// standard deviation of rewards around the mean
+    pub noise: f64,
+}
+
+impl Default for ContextualBanditConfig {
+    /// 5 arms with 4 features, and weights picked from a standard normal
+    fn default() -> Self {
+        Self {
+            weights: (0..5)
+                .map(|_| (0..4).map(|_| normal(0., 1.)).collect())
+                .collect(),
+            noise: 0.1,
+        }
+    }
+}
+
+/// Bandit with new features every epoch, from a standard normal, with the last always 1 as a bias.
+/// The state is the features, which are also returned by `Environment::observation` for e.g. `LinUcb`
+#[derive(Clone)]
+pub struct ContextualBandit {
+    weights: Vec<Vec<f64>>,
+    noise: f64,
+    features: Vec<f64>,
+    regret: f64,
+}
+
+impl Default for ContextualBandit {
+    fn default() -> Self {
+        Self::new(ContextualBanditConfig::default())
+    }
+}
+
+impl ContextualBandit {
+    /// Panics if there are no arms, or arms have different numbers of weights
+    pub fn new(config: ContextualBanditConfig) -> Self {
+        let features = config.weights.first().map_or(0, Vec::len);
+        assert!(
+            features > 0 && config.weights.iter().all(|w| w.len() == features),
+            "contextual bandit arms need the same number of weights"
+        );
+        let mut env = Self {
+            weights: config.weights,
+            noise: config.noise,
+            features: vec![],
+            regret: 0.,
+        };
+        env.reset(0);
+        env
+    }
+
+    /// Expected reward of each arm for the current features
+    pub fn means(&self) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|w| w.iter().zip(&self.features).map(|(w, x)| w * x).sum())
+            .collect()
+    }
+
+    /// Index of the arm with the highest mean for the current features
+    pub fn best_arm(&self) -> usize {
+        best(self.means().into_iter())
+    }
+}
+
+impl Environment<Vec<f64>, usize> for ContextualBandit {
+    fn state(&self) -> Vec<f64> {
+        self.features.clone()
+    }
+
+    /// New features
+    fn reset(&mut self, _epoch: usize) {
+        let count = self.weights[0].len();
+        self.features = (0..count)
+            .map(|i| if i == count - 1 { 1. } else { normal(0., 1.) })
+            .collect();
+    }
+
+    fn all_actions(&self) -> Vec<usize> {
+        (0..self.weights.len()).collect()
+    }
+
+    fn take_action_get_reward(&mut self, action: &usize) -> f64 {
+        let means = self.means();
+        self.regret += means[best(means.iter().copied())] - means[*action];
+        normal(means[*action], self.noise)
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        true
+    }
+
+    fn regret(&self) -> Option<f64> {
+        Some(self.regret)
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.features.iter().map(|&x| x as f32).collect()
+    }
+}
+
+/// Index of the highest value, or the first if tied
+fn best(values: impl Iterator<Item = f64>) -> usize {
+    values
+        .enumerate()
+        .fold((0, f64::MIN), |best, x| if x.1 > best.1 { x } else { best })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regret_is_each_pull_of_a_worse_arm() {
+        let mut env = Bandit::bernoulli(&[0.2, 0.5, 0.8]);
+        let gaps = [0.6, 0.3, 0.];
+        let mut expected = 0.;
+        for arm in [0, 1, 1, 2, 0, 2] {
+            env.take_action_get_reward(&arm);
+            expected += gaps[arm];
+            assert!((env.regret().unwrap() - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn contextual_regret_is_against_the_best_arm_for_the_features() {
+        let mut env = ContextualBandit::new(ContextualBanditConfig {
+            weights: vec![vec![1., 0.], vec![-1., 0.5]],
+            noise: 0.,
+        });
+        let mut expected = 0.;
+        for epoch in 0..10 {
+            env.reset(epoch);
+            let means = env.means();
+            let worst = if means[0] < means[1] { 0 } else { 1 };
+            env.take_action_get_reward(&worst);
+            expected += (means[0] - means[1]).abs();
+            assert!((env.regret().unwrap() - expected).abs() < 1e-9);
+        }
+    }
+}
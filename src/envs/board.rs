@@ -0,0 +1,134 @@
+use super::{GameRewards, Mark};
+
+/// Grid of pieces for 2 player games, and the end of game rules shared by them
+#[derive(Clone)]
+pub(super) struct Board {
+    pub width: usize,
+    pub height: usize,
+    /// in a row to win
+    pub connect: usize,
+    /// player in each cell, row by row from the top
+    pub cells: Vec<Option<usize>>,
+    pub current: usize,
+    pub over: bool,
+    pixels: Vec<u8>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize, connect: usize) -> Self {
+        Self {
+            width,
+            height,
+            connect,
+            cells: vec![None; width * height],
+            current: 0,
+            over: false,
+            pixels: vec![0; width * height * 3],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = None);
+        self.current = 0;
+        self.over = false;
+    }
+
+    /// Cells as seen by the current player
+    pub fn marks(&self) -> Vec<Mark> {
+        self.cells
+            .iter()
+            .map(|c| match c {
+                None => Mark::Empty,
+                Some(p) if *p == self.current => Mark::Mine,
+                Some(_) => Mark::Theirs,
+            })
+            .collect()
+    }
+
+    /// 1 for the current player's pieces, -1 for the other's and 0 for empty
+    pub fn observation(&self) -> Vec<f32> {
+        self.marks()
+            .into_iter()
+            .map(|m| match m {
+                Mark::Empty => 0.,
+                Mark::Mine => 1.,
+                Mark::Theirs => -1.,
+            })
+            .collect()
+    }
+
+    /// Places the current player's piece at `index`, or ends the game as an invalid move if `index` is `None`,
+    /// off the board or taken.
+    /// Returns the rewards, and passes the turn if the game goes on
+    pub fn play(&mut self, index: Option<usize>, rewards: &GameRewards) -> Vec<f64> {
+        let player = self.current;
+        let mut result = vec![0.; 2];
+        let Some(index) =
+            index.filter(|&i| !self.over && self.cells.get(i).is_some_and(Option::is_none))
+        else {
+            self.over = true;
+            result[player] = rewards.invalid;
+            return result;
+        };
+        self.cells[index] = Some(player);
+        if self.wins(index) {
+            self.over = true;
+            result[player] = rewards.win;
+            result[1 - player] = rewards.loss;
+        } else if self.cells.iter().all(Option::is_some) {
+            self.over = true;
+            result = vec![rewards.draw; 2];
+        } else {
+            self.current = 1 - player;
+        }
+        result
+    }
+
+    /// Whether the piece at `index` is part of `connect` in a row
+    fn wins(&self, index: usize) -> bool {
+        let player = self.cells[index];
+        let (x, y) = ((index % self.width) as isize, (index / self.width) as isize);
+        let same = |dx: isize, dy: isize| {
+            (1..self.connect as isize)
+                .map(|i| (x + dx * i, y + dy * i))
+                .take_while(|&(x, y)| {
+                    (0..self.width as isize).contains(&x)
+                        && (0..self.height as isize).contains(&y)
+                        && self.cells[y as usize * self.width + x as usize] == player
+                })
+                .count()
+        };
+        [(1, 0), (0, 1), (1, 1), (1, -1)]
+            .iter()
+            .any(|&(dx, dy)| 1 + same(dx, dy) + same(-dx, -dy) >= self.connect)
+    }
+
+    /// Pixel per cell, with the first player red and the second yellow
+    pub fn image(&mut self) -> &[u8] {
+        for (cell, pixel) in self.cells.iter().zip(self.pixels.chunks_mut(3)) {
+            pixel.copy_from_slice(match cell {
+                None => &[30, 60, 160],
+                Some(0) => &[220, 30, 30],
+                Some(_) => &[240, 220, 0],
+            });
+        }
+        &self.pixels
+    }
+
+    /// `X` for the first player and `O` for the second, with the last row of `footer` e.g. column numbers
+    pub fn render_text(&self, footer: &str) -> String {
+        let mut text = String::new();
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                text.push_str(match cell {
+                    None => " .",
+                    Some(0) => " \x1b[1;31mX\x1b[0m",
+                    Some(_) => " \x1b[1;33mO\x1b[0m",
+                });
+            }
+            text.push('\n');
+        }
+        text.push_str(footer);
+        text
+    }
+}
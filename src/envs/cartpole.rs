@@ -0,0 +1,202 @@
+// CartPole
+// Push a cart left or right to keep a pole balanced on it, as in gymnasium CartPole-v1.
+// Ends when the pole leans more than 12 degrees, the cart leaves the track, or after `max_steps`.
+// Reward is 1 per step, so the max is `max_steps`
+
+use crate::environment::{draw::Canvas, Environment, Frame};
+
+use super::{bin, uniform};
+
+const GRAVITY: f64 = 9.8;
+const MASS_CART: f64 = 1.0;
+const MASS_POLE: f64 = 0.1;
+const TOTAL_MASS: f64 = MASS_CART + MASS_POLE;
+/// half the pole's length
+const LENGTH: f64 = 0.5;
+const POLE_MASS_LENGTH: f64 = MASS_POLE * LENGTH;
+const FORCE: f64 = 10.0;
+/// seconds between steps
+const TAU: f64 = 0.02;
+const X_LIMIT: f64 = 2.4;
+const THETA_LIMIT: f64 = 12. * 2. * std::f64::consts::PI / 360.;
+/// range of each value used for bins. Velocities have no limit, so values outside go in the end bins
+const BOUNDS: [(f64, f64); 4] = [
+    (-X_LIMIT, X_LIMIT),
+    (-3., 3.),
+    (-THETA_LIMIT, THETA_LIMIT),
+    (-3.5, 3.5),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CartPoleAction {
+    Left,
+    Right,
+}
+
+pub struct CartPoleConfig {
+    /// 500 as in CartPole-v1
+    pub max_steps: usize,
+    /// bins for each of position, velocity, angle and angular velocity in `state`
+    pub bins: usize,
+}
+
+impl Default for CartPoleConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 500,
+            bins: 6,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CartPole {
+    max_steps: usize,
+    bins: usize,
+    /// position, velocity, angle and angular velocity
+    values: [f64; 4],
+}
+
+impl Default for CartPole {
+    fn default() -> Self {
+        Self::new(CartPoleConfig::default())
+    }
+}
+
+impl CartPole {
+    pub fn new(config: CartPoleConfig) -> Self {
+        Self {
+            max_steps: config.max_steps,
+            bins: config.bins.max(1),
+            values: [0.; 4],
+        }
+    }
+
+    /// position, velocity, angle and angular velocity
+    pub fn values(&self) -> [f64; 4] {
+        self.values
+    }
+}
+
+impl Environment<[usize; 4], CartPoleAction> for CartPole {
+    /// bin of each value
+    fn state(&self) -> [usize; 4] {
+        std::array::from_fn(|i| bin(self.values[i], BOUNDS[i].0, BOUNDS[i].1, self.bins))
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.values = std::array::from_fn(|_| uniform(-0.05, 0.05));
+    }
+
+    fn all_actions(&self) -> Vec<CartPoleAction> {
+        vec![CartPoleAction::Left, CartPoleAction::Right]
+    }
+
+    fn take_action_get_reward(&mut self, action: &CartPoleAction) -> f64 {
+        let [x, x_dot, theta, theta_dot] = self.values;
+        let force = match action {
+            CartPoleAction::Left => -FORCE,
+            CartPoleAction::Right => FORCE,
+        };
+        let (sin, cos) = theta.sin_cos();
+        let temp = (force + POLE_MASS_LENGTH * theta_dot * theta_dot * sin) / TOTAL_MASS;
+        let theta_acc = (GRAVITY * sin - cos * temp)
+            / (LENGTH * (4.0 / 3.0 - MASS_POLE * cos * cos / TOTAL_MASS));
+        let x_acc = temp - POLE_MASS_LENGTH * theta_acc * cos / TOTAL_MASS;
+        //euler
+        self.values = [
+            x + TAU * x_dot,
+            x_dot + TAU * x_acc,
+            theta + TAU * theta_dot,
+            theta_dot + TAU * theta_acc,
+        ];
+        1.
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        self.terminated() || step >= self.max_steps
+    }
+
+    fn terminated(&self) -> bool {
+        self.values[0].abs() > X_LIMIT || self.values[2].abs() > THETA_LIMIT
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        let (width, height) = (150, 100);
+        let scale = width as f64 / (X_LIMIT * 2.);
+        let mut canvas = Canvas::new(width, height, [255, 255, 255]);
+        let track = 75;
+        canvas.line((0, track), (width as i64, track), [0, 0, 0]);
+        let cart = (self.values[0] * scale + width as f64 / 2.) as i64;
+        canvas.fill_rect(
+            (cart - 10).max(0) as usize,
+            track as usize - 10,
+            20,
+            10,
+            [0, 0, 0],
+        );
+        let pole = 2. * LENGTH * scale;
+        let (sin, cos) = self.values[2].sin_cos();
+        let top = (cart + (pole * sin) as i64, track - 10 - (pole * cos) as i64);
+        canvas.line((cart, track - 10), top, [202, 152, 101]);
+        canvas.into_frame()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.values.iter().map(|x| *x as f32).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: [f64; 4], b: [f64; 4]) {
+        assert!(
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn step_matches_gymnasium() {
+        //from the CartPole-v1 step equations, with the same starting values
+        let mut env = CartPole {
+            values: [0.01, -0.02, 0.03, 0.04],
+            ..Default::default()
+        };
+        assert_eq!(env.take_action_get_reward(&CartPoleAction::Right), 1.);
+        assert_close(
+            env.values(),
+            [0.0096, 0.174679195748, 0.0308, -0.243068717960],
+        );
+        env.values = [0.01, -0.02, 0.03, 0.04];
+        env.take_action_get_reward(&CartPoleAction::Left);
+        assert_close(
+            env.values(),
+            [0.0096, -0.215539017103, 0.0308, 0.341995223776],
+        );
+    }
+
+    #[test]
+    fn terminates_past_the_limits() {
+        let mut env = CartPole::default();
+        for (values, terminated) in [
+            ([X_LIMIT, 0., THETA_LIMIT, 0.], false),
+            ([-X_LIMIT, 0., -THETA_LIMIT, 0.], false),
+            ([X_LIMIT + 1e-6, 0., 0., 0.], true),
+            ([-X_LIMIT - 1e-6, 0., 0., 0.], true),
+            ([0., 0., THETA_LIMIT + 1e-6, 0.], true),
+            ([0., 0., -THETA_LIMIT - 1e-6, 0.], true),
+        ] {
+            env.values = values;
+            assert_eq!(env.terminated(), terminated, "{values:?}");
+            assert_eq!(env.should_stop(1), terminated);
+        }
+        //cut short, not terminated
+        env.values = [0.; 4];
+        assert!(env.should_stop(500));
+        assert!(!env.terminated());
+    }
+}
@@ -0,0 +1,109 @@
+// Connect Four
+// 2 players take turns to drop a piece into a column, where it falls to the lowest empty cell.
+// 4 in a row in any direction wins, and a full board is a draw. Dropping into a full column loses the game
+//
+//  . . . . . . .
+//  . . . . . . .
+//  . . . O . . .
+//  . . . X O . .
+//  . . X X O . .
+//  . X O X O X .
+
+use crate::environment::{turnbased::TurnBasedEnvironment, Frame};
+
+use super::{board::Board, GameRewards, Mark};
+
+#[derive(Clone)]
+pub struct ConnectFourConfig {
+    pub width: usize,
+    pub height: usize,
+    /// in a row to win
+    pub connect: usize,
+    pub rewards: GameRewards,
+}
+
+impl Default for ConnectFourConfig {
+    fn default() -> Self {
+        Self {
+            width: 7,
+            height: 6,
+            connect: 4,
+            rewards: GameRewards::default(),
+        }
+    }
+}
+
+/// State is the cells row by row from the top, as seen by the current player. Actions are the column to drop into
+#[derive(Clone)]
+pub struct ConnectFour {
+    rewards: GameRewards,
+    board: Board,
+}
+
+impl Default for ConnectFour {
+    fn default() -> Self {
+        Self::new(ConnectFourConfig::default())
+    }
+}
+
+impl ConnectFour {
+    pub fn new(config: ConnectFourConfig) -> Self {
+        Self {
+            rewards: config.rewards,
+            board: Board::new(config.width, config.height, config.connect),
+        }
+    }
+}
+
+impl TurnBasedEnvironment<Vec<Mark>, usize> for ConnectFour {
+    fn state(&self) -> Vec<Mark> {
+        self.board.marks()
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.board.reset();
+    }
+
+    fn current_player(&self) -> usize {
+        self.board.current
+    }
+
+    fn all_actions(&self) -> Vec<usize> {
+        (0..self.board.width).collect()
+    }
+
+    /// Columns which are not full
+    fn valid_actions(&self) -> Vec<usize> {
+        (0..self.board.width)
+            .filter(|&x| self.board.cells[x].is_none())
+            .collect()
+    }
+
+    fn take_action_get_rewards(&mut self, action: &usize) -> Vec<f64> {
+        let board = &self.board;
+        //lowest empty cell of the column
+        let index = (0..board.height)
+            .rev()
+            .map(|y| y * board.width + action)
+            .find(|&i| *action < board.width && board.cells[i].is_none());
+        self.board.play(index, &self.rewards)
+    }
+
+    fn is_over(&self) -> bool {
+        self.board.over
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        let (width, height) = (self.board.width, self.board.height);
+        Frame::rgb(width, height, self.board.image())
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.board.observation()
+    }
+
+    fn render_text(&self) -> String {
+        let columns: String = (0..self.board.width).map(|x| format!(" {x}")).collect();
+        self.board.render_text(&columns)
+    }
+}
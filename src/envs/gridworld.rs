@@ -0,0 +1,462 @@
+// GridWorld
+// Move around a grid drawn as text, to reach a goal while avoiding holes and cliffs.
+// FrozenLake and CliffWalking as in gymnasium are built in, or draw your own map e.g.
+//
+// S..#.
+// .#.$G
+// CCCCH
+
+use crate::environment::{Environment, Frame};
+
+use super::Point;
+
+pub const FROZEN_LAKE_4X4: &str = "\
+SFFF
+FHFH
+FFFH
+HFFG";
+
+pub const FROZEN_LAKE_8X8: &str = "\
+SFFFFFFF
+FFFFFFFF
+FFFHFFFF
+FFFFFHFF
+FFFHFFFF
+FHHFFFHF
+FHFFHFHF
+FFFHFFFG";
+
+pub const CLIFF_WALKING: &str = "\
+............
+............
+............
+SCCCCCCCCCCG";
+
+/// What each character of a map means
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cell {
+    /// `.`, `F` or space
+    Empty,
+    /// `#` can not be entered
+    Wall,
+    /// `S` where epochs start. If there are several, one is picked at random
+    Start,
+    /// `G` ends the epoch
+    Goal,
+    /// `H` ends the epoch
+    Hole,
+    /// `C` sends us back to the start
+    Cliff,
+    /// any other character in `GridWorldConfig::cell_rewards`, which gives its reward as well as the step reward
+    Reward(f64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridAction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Reward for entering each kind of cell
+#[derive(Clone)]
+pub struct GridRewards {
+    pub step: f64,
+    /// moving into a wall or off the map. We stay in the same place
+    pub wall: f64,
+    pub goal: f64,
+    pub hole: f64,
+    pub cliff: f64,
+}
+
+impl Default for GridRewards {
+    fn default() -> Self {
+        Self {
+            step: -1.,
+            wall: -1.,
+            goal: 10.,
+            hole: -10.,
+            cliff: -100.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GridWorldConfig {
+    /// 1 row per line, all the same length. See `Cell` for the characters
+    pub map: String,
+    pub rewards: GridRewards,
+    /// rewards for other characters e.g. `('$', 5.)`
+    pub cell_rewards: Vec<(char, f64)>,
+    /// chance of moving at right angles to the chosen direction instead, e.g. on ice
+    pub slippery: f64,
+    pub max_steps: usize,
+}
+
+impl Default for GridWorldConfig {
+    fn default() -> Self {
+        Self {
+            map: FROZEN_LAKE_4X4.to_string(),
+            rewards: GridRewards::default(),
+            cell_rewards: vec![],
+            slippery: 0.,
+            max_steps: 100,
+        }
+    }
+}
+
+/// The state is the position on the grid
+#[derive(Clone)]
+pub struct GridWorld {
+    cells: Vec<Vec<Cell>>,
+    /// characters of the map, for `render_text`
+    map: Vec<Vec<char>>,
+    starts: Vec<Point>,
+    rewards: GridRewards,
+    slippery: f64,
+    max_steps: usize,
+    current: Point,
+    pixels: Vec<u8>,
+}
+
+impl Default for GridWorld {
+    fn default() -> Self {
+        Self::new(GridWorldConfig::default())
+    }
+}
+
+impl GridWorld {
+    /// Panics if there is no `S`, rows are not the same length, or a character is unknown
+    pub fn new(config: GridWorldConfig) -> Self {
+        let map: Vec<Vec<char>> = config
+            .map
+            .lines()
+            .map(|line| line.chars().collect())
+            .filter(|row: &Vec<char>| !row.is_empty())
+            .collect();
+        assert!(
+            map.iter().all(|row| row.len() == map[0].len()),
+            "grid world map rows must be the same length"
+        );
+        let cells: Vec<Vec<Cell>> = map
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| match c {
+                        '.' | 'F' | ' ' => Cell::Empty,
+                        '#' => Cell::Wall,
+                        'S' => Cell::Start,
+                        'G' => Cell::Goal,
+                        'H' => Cell::Hole,
+                        'C' => Cell::Cliff,
+                        _ => Cell::Reward(
+                            config
+                                .cell_rewards
+                                .iter()
+                                .find(|x| x.0 == c)
+                                .unwrap_or_else(|| panic!("unknown grid world cell {c}"))
+                                .1,
+                        ),
+                    })
+                    .collect()
+            })
+            .collect();
+        let starts: Vec<Point> = cells
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|x| *x.1 == Cell::Start)
+                    .map(move |(x, _)| Point::new(x, y))
+            })
+            .collect();
+        assert!(!starts.is_empty(), "grid world map needs an S");
+
+        Self {
+            current: starts[0],
+            pixels: vec![0; cells.len() * cells[0].len() * 3],
+            cells,
+            map,
+            starts,
+            rewards: config.rewards,
+            slippery: config.slippery,
+            max_steps: config.max_steps,
+        }
+    }
+
+    /// FrozenLake: reach the goal across the ice without falling in a hole.
+    /// Reward is 1 for the goal and 0 otherwise. When `slippery`, we only move in the chosen direction a third of the time
+    pub fn frozen_lake(slippery: bool) -> Self {
+        Self::new(GridWorldConfig {
+            map: FROZEN_LAKE_4X4.to_string(),
+            slippery: if slippery { 2. / 3. } else { 0. },
+            ..Self::frozen_lake_config()
+        })
+    }
+
+    /// FrozenLake on the larger 8x8 map, which is stopped after 200 steps
+    pub fn frozen_lake_8x8(slippery: bool) -> Self {
+        Self::new(GridWorldConfig {
+            map: FROZEN_LAKE_8X8.to_string(),
+            slippery: if slippery { 2. / 3. } else { 0. },
+            max_steps: 200,
+            ..Self::frozen_lake_config()
+        })
+    }
+
+    fn frozen_lake_config() -> GridWorldConfig {
+        GridWorldConfig {
+            rewards: GridRewards {
+                step: 0.,
+                wall: 0.,
+                goal: 1.,
+                hole: 0.,
+                cliff: 0.,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// CliffWalking: walk along the cliff edge to the goal. Each step is -1, and falling off the cliff is -100
+    /// and back to the start. The best return is -13
+    pub fn cliff_walking() -> Self {
+        Self::new(GridWorldConfig {
+            map: CLIFF_WALKING.to_string(),
+            rewards: GridRewards {
+                step: -1.,
+                wall: -1.,
+                goal: -1.,
+                hole: -100.,
+                cliff: -100.,
+            },
+            max_steps: 200,
+            ..Default::default()
+        })
+    }
+
+    pub fn cell(&self, point: Point) -> Cell {
+        self.cells[point.y][point.x]
+    }
+
+    /// The cell next to `from` in the direction of `action`, if it is on the map
+    fn next(&self, from: Point, action: &GridAction) -> Option<Point> {
+        let Point { x, y } = from;
+        let point = match action {
+            GridAction::Up if y > 0 => Point::new(x, y - 1),
+            GridAction::Down if y < self.cells.len() - 1 => Point::new(x, y + 1),
+            GridAction::Left if x > 0 => Point::new(x - 1, y),
+            GridAction::Right if x < self.cells[0].len() - 1 => Point::new(x + 1, y),
+            _ => return None,
+        };
+        Some(point)
+    }
+
+    fn save_image(&mut self) {
+        let width = self.cells[0].len();
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let colour = match cell {
+                    Cell::Empty => [220, 230, 240],
+                    Cell::Wall => [60, 60, 60],
+                    Cell::Start => [150, 150, 250],
+                    Cell::Goal => [0, 200, 0],
+                    Cell::Hole => [0, 0, 80],
+                    Cell::Cliff => [140, 70, 20],
+                    Cell::Reward(r) if *r >= 0. => [240, 220, 0],
+                    Cell::Reward(_) => [240, 120, 0],
+                };
+                let start = (y * width + x) * 3;
+                self.pixels[start..start + 3].copy_from_slice(&colour);
+            }
+        }
+        let start = (self.current.y * width + self.current.x) * 3;
+        self.pixels[start..start + 3].copy_from_slice(&[255, 0, 255]);
+    }
+}
+
+impl Environment<Point, GridAction> for GridWorld {
+    fn state(&self) -> Point {
+        self.current
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.current = self.starts[fastrand::usize(0..self.starts.len())];
+    }
+
+    fn all_actions(&self) -> Vec<GridAction> {
+        vec![
+            GridAction::Up,
+            GridAction::Down,
+            GridAction::Left,
+            GridAction::Right,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &GridAction) -> f64 {
+        let action = if fastrand::f64() < self.slippery {
+            //at right angles, either way
+            match (action, fastrand::bool()) {
+                (GridAction::Up | GridAction::Down, true) => GridAction::Left,
+                (GridAction::Up | GridAction::Down, false) => GridAction::Right,
+                (GridAction::Left | GridAction::Right, true) => GridAction::Up,
+                (GridAction::Left | GridAction::Right, false) => GridAction::Down,
+            }
+        } else {
+            action.clone()
+        };
+
+        let next = self
+            .next(self.current, &action)
+            .filter(|&p| self.cell(p) != Cell::Wall);
+        let Some(next) = next else {
+            return self.rewards.wall;
+        };
+        self.current = next;
+        match self.cell(next) {
+            Cell::Goal => self.rewards.goal,
+            Cell::Hole => self.rewards.hole,
+            Cell::Cliff => {
+                self.current = self.starts[fastrand::usize(0..self.starts.len())];
+                self.rewards.cliff
+            }
+            Cell::Reward(reward) => self.rewards.step + reward,
+            Cell::Empty | Cell::Start | Cell::Wall => self.rewards.step,
+        }
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        step >= self.max_steps || matches!(self.cell(self.current), Cell::Goal | Cell::Hole)
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.save_image();
+        Frame::rgb(self.cells[0].len(), self.cells.len(), &self.pixels)
+    }
+
+    ///the map, with us in magenta
+    fn render_text(&self) -> String {
+        let mut text = String::new();
+        for (y, row) in self.map.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if self.current == Point::new(x, y) {
+                    text.push_str("\x1b[45m");
+                    text.push(*c);
+                    text.push_str("\x1b[0m");
+                } else {
+                    text.push(*c);
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(map: &str, slippery: f64) -> GridWorld {
+        GridWorld::new(GridWorldConfig {
+            map: map.to_string(),
+            slippery,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    #[should_panic(expected = "rows must be the same length")]
+    fn ragged_rows_panic() {
+        grid("S..\n..\n..G", 0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "needs an S")]
+    fn map_without_start_panics() {
+        grid("...\n..G", 0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown grid world cell")]
+    fn unknown_cell_panics() {
+        grid("S.x", 0.);
+    }
+
+    #[test]
+    fn parses_cells() {
+        let env = GridWorld::new(GridWorldConfig {
+            map: "S#$\n.HG\n".to_string(),
+            cell_rewards: vec![('$', 5.)],
+            ..Default::default()
+        });
+        let cells: Vec<Cell> = (0..2)
+            .flat_map(|y| (0..3).map(move |x| Point::new(x, y)))
+            .map(|p| env.cell(p))
+            .collect();
+        use Cell::*;
+        assert_eq!(cells, [Start, Wall, Reward(5.), Empty, Hole, Goal]);
+    }
+
+    #[test]
+    fn walls_and_edges_block_moves() {
+        let mut env = grid("S#G", 0.);
+        let wall = GridRewards::default().wall;
+        assert_eq!(env.take_action_get_reward(&GridAction::Right), wall);
+        assert_eq!(env.take_action_get_reward(&GridAction::Up), wall);
+        assert_eq!(env.state(), Point::new(0, 0));
+    }
+
+    #[test]
+    fn cliff_sends_back_to_start() {
+        let mut env = GridWorld::cliff_walking();
+        env.reset(1);
+        env.take_action_get_reward(&GridAction::Up);
+        env.take_action_get_reward(&GridAction::Right);
+        assert_eq!(env.state(), Point::new(1, 2));
+        assert_eq!(env.take_action_get_reward(&GridAction::Down), -100.);
+        assert_eq!(env.state(), Point::new(0, 3));
+        assert!(!env.should_stop(3));
+    }
+
+    #[test]
+    fn holes_and_goal_end_the_epoch() {
+        let mut env = GridWorld::frozen_lake(false);
+        env.reset(1);
+        env.take_action_get_reward(&GridAction::Down);
+        assert!(!env.should_stop(1));
+        assert_eq!(env.take_action_get_reward(&GridAction::Right), 0.);
+        assert_eq!(env.cell(env.state()), Cell::Hole);
+        assert!(env.should_stop(2));
+
+        env.current = Point::new(3, 2);
+        assert_eq!(env.take_action_get_reward(&GridAction::Down), 1.);
+        assert!(env.should_stop(1));
+    }
+
+    /// How often `Right` from the middle of an open map moves sideways instead
+    fn slips(slippery: f64) -> f64 {
+        fastrand::seed(4);
+        let mut env = grid("...\n.S.\n...", slippery);
+        let tries = 3000;
+        let mut sideways = 0;
+        for _ in 0..tries {
+            env.reset(1);
+            env.take_action_get_reward(&GridAction::Right);
+            let Point { x, y } = env.state();
+            assert_ne!(x, 0, "never slips backwards");
+            if y != 1 {
+                sideways += 1;
+            }
+        }
+        sideways as f64 / tries as f64
+    }
+
+    #[test]
+    fn slips_sideways() {
+        assert_eq!(slips(0.), 0.);
+        assert!((slips(GridWorld::frozen_lake(true).slippery) - 2. / 3.).abs() < 0.03);
+    }
+}
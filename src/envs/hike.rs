@@ -0,0 +1,319 @@
+use crate::environment::{Environment, Frame};
+
+use super::Point;
+
+/// Hike
+/// based on https://adventofcode.com/2022/day/12, but modified so it is a bit easier (only ascents! thus will differ from original answer)
+/// This is a harder learn than taxi (start there first)
+/// Aim is to go from start point to end point, only by staying at same height (a-z) or ascending by maximum of 1.
+/// Cells can only be visited once per epoch
+/// The state set is much bigger, so it takes a lot of epochs to learn, and sometimes it won't learn it.
+/// Most likely the optimum solution won't be reached, but it tries to find a better and better solution each time
+pub const MAP: &str = include_str!("hike.txt");
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HikeAction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HikeState {
+    pub current: Point,
+}
+
+#[derive(Clone)]
+pub struct HikeRewards {
+    pub step: f64,
+    /// taken off every valid step, times the distance left to the end. This guides learning but can change the best route,
+    /// so set to 0 and use `PotentialShaping` to guide it without changing the best route
+    pub distance: f64,
+    /// reaching the end, instead of `step`
+    pub found: f64,
+    /// a step which is too steep, already visited, or off the map. We stay in the same place
+    pub invalid: f64,
+}
+
+impl Default for HikeRewards {
+    fn default() -> Self {
+        Self {
+            step: -100.,
+            distance: 1.,
+            found: 1000.,
+            invalid: -200.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HikeConfig {
+    /// heights as letters a-z, 1 row per line
+    pub map: String,
+    pub start: Point,
+    pub end: Point,
+    pub rewards: HikeRewards,
+    /// stops once the step count goes past this, so an epoch can be `max_steps` + 1 steps long
+    pub max_steps: usize,
+}
+
+impl Default for HikeConfig {
+    fn default() -> Self {
+        Self {
+            map: MAP.to_string(),
+            start: Point::new(0, 20),
+            end: Point::new(136, 20),
+            rewards: HikeRewards::default(),
+            max_steps: 650,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct GridVal {
+    height: u8,
+    visited: bool,
+}
+
+#[derive(Clone)]
+pub struct Hike {
+    grid: Vec<Vec<GridVal>>,
+    start: Point,
+    end: Point,
+    rewards: HikeRewards,
+    max_steps: usize,
+    /// fewest steps to the end so far
+    best: Option<usize>,
+    /// closest distance to the end so far
+    closest: f64,
+    history: Vec<Point>,
+    best_route: Vec<Point>,
+    pixels: Vec<u8>,
+    state: HikeState,
+}
+
+impl Default for Hike {
+    fn default() -> Self {
+        Self::new(HikeConfig::default())
+    }
+}
+
+impl Hike {
+    /// Panics if `start` or `end` are not on the map
+    pub fn new(config: HikeConfig) -> Self {
+        let grid: Vec<Vec<GridVal>> = config
+            .map
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.bytes()
+                    .map(|height| GridVal {
+                        height,
+                        visited: false,
+                    })
+                    .collect()
+            })
+            .collect();
+        for point in [config.start, config.end] {
+            assert!(
+                grid.get(point.y).is_some_and(|row| point.x < row.len()),
+                "{point:?} is not on the map"
+            );
+        }
+        let width = grid[0].len();
+        let mut hike = Hike {
+            pixels: vec![0u8; width * grid.len() * 3],
+            grid,
+            start: config.start,
+            end: config.end,
+            rewards: config.rewards,
+            max_steps: config.max_steps,
+            best: None,
+            closest: f64::MAX,
+            history: vec![],
+            best_route: vec![],
+            state: HikeState {
+                current: config.start,
+            },
+        };
+        hike.reset(0);
+        hike.update_pixels();
+        hike
+    }
+
+    /// Fewest steps taken to reach the end so far
+    pub fn best(&self) -> Option<usize> {
+        self.best
+    }
+
+    /// Shortest route to the end so far, or the route which got closest if it has not been reached
+    pub fn best_route(&self) -> &[Point] {
+        &self.best_route
+    }
+
+    fn valid_actions(&self) -> Vec<HikeAction> {
+        let grid = &self.grid;
+        let Point { x, y } = self.state.current;
+        let can_step = |to: &GridVal| {
+            !to.visited && (to.height == grid[y][x].height || to.height == grid[y][x].height + 1)
+        };
+
+        let mut valid = vec![];
+        if y > 0 && can_step(&grid[y - 1][x]) {
+            valid.push(HikeAction::Up)
+        }
+        if y < grid.len() - 1 && can_step(&grid[y + 1][x]) {
+            valid.push(HikeAction::Down)
+        }
+        if x > 0 && can_step(&grid[y][x - 1]) {
+            valid.push(HikeAction::Left)
+        }
+        if x < grid[0].len() - 1 && can_step(&grid[y][x + 1]) {
+            valid.push(HikeAction::Right)
+        }
+        valid
+    }
+
+    //for display
+    fn update_pixels(&mut self) {
+        let width = self.grid[0].len();
+        let mut set = |p: &Point, col: [u8; 3]| {
+            let start = (p.y * width + p.x) * 3;
+            self.pixels[start..start + 3].copy_from_slice(&col);
+        };
+
+        //height
+        for (iy, row) in self.grid.iter().enumerate() {
+            for (ix, p) in row.iter().enumerate() {
+                let col = ((b'z' as f32 - p.height as f32) / 26.0 * 255.0) as u8;
+                set(&Point::new(ix, iy), [col, col, col]);
+            }
+        }
+        set(&self.start, [0, 255, 0]);
+        let end = self.end;
+        let start = (end.y * width + end.x) * 3;
+        self.pixels[start] = 255;
+
+        for p in &self.best_route {
+            let start = (p.y * width + p.x) * 3;
+            self.pixels[start..start + 3].copy_from_slice(&[150, 150, 250]);
+        }
+
+        let found = self.history.last() == Some(&self.end);
+        let col = if found { [0, 255, 0] } else { [0, 0, 255] };
+        for p in &self.history {
+            let start = (p.y * width + p.x) * 3;
+            self.pixels[start..start + 3].copy_from_slice(&col);
+        }
+    }
+
+    fn on_finish(&mut self, step: usize) {
+        let dis = self.state.current.distance(&self.end);
+        if dis < self.closest {
+            self.best_route = self.history.clone();
+            self.closest = dis;
+        }
+
+        if dis < 1. && step < self.best.unwrap_or(usize::MAX) {
+            self.best = Some(step);
+            self.best_route = self.history.clone();
+        }
+    }
+}
+
+impl Environment<HikeState, HikeAction> for Hike {
+    fn state(&self) -> HikeState {
+        self.state.clone()
+    }
+
+    fn all_actions(&self) -> Vec<HikeAction> {
+        vec![
+            HikeAction::Up,
+            HikeAction::Down,
+            HikeAction::Left,
+            HikeAction::Right,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &HikeAction) -> f64 {
+        if !self.valid_actions().contains(action) {
+            //This repeats for the max number of steps
+            return self.rewards.invalid;
+        }
+        let current = &mut self.state.current;
+        match action {
+            HikeAction::Up => current.y -= 1,
+            HikeAction::Down => current.y += 1,
+            HikeAction::Left => current.x -= 1,
+            HikeAction::Right => current.x += 1,
+        }
+        let current = *current;
+        self.grid[current.y][current.x].visited = true;
+        self.history.push(current);
+
+        //- for each step + if found
+        let new_dis = current.distance(&self.end);
+        let modi = if new_dis < 1. {
+            self.rewards.found
+        } else {
+            self.rewards.step
+        };
+        -new_dis * self.rewards.distance + modi
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        let finished = step > self.max_steps || self.state.current == self.end;
+        if finished {
+            self.on_finish(step);
+            self.update_pixels();
+        }
+        finished
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.state = HikeState {
+            current: self.start,
+        };
+        for row in self.grid.iter_mut() {
+            for x in row {
+                x.visited = false;
+            }
+        }
+        self.grid[self.start.y][self.start.x].visited = true;
+        self.history.clear();
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        Frame::rgb(self.grid[0].len(), self.grid.len(), &self.pixels)
+    }
+
+    ///heights as letters, with current route in blue, start in green and end in red
+    fn render_text(&self) -> String {
+        let mut text = String::new();
+        for (iy, row) in self.grid.iter().enumerate() {
+            for (ix, p) in row.iter().enumerate() {
+                let point = Point::new(ix, iy);
+                let colour = if point == self.start {
+                    "\x1b[42m"
+                } else if point == self.end {
+                    "\x1b[41m"
+                } else if p.visited {
+                    "\x1b[44m"
+                } else {
+                    ""
+                };
+                text.push_str(colour);
+                text.push(p.height as char);
+                if !colour.is_empty() {
+                    text.push_str("\x1b[0m");
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
@@ -0,0 +1,203 @@
+// MountainCar
+// Drive an underpowered car out of a valley to the flag on the right, as in gymnasium MountainCar-v0.
+// The car has to build momentum by going back and forth.
+// Reward is -1 per step until the flag is reached, or after `max_steps`
+
+use crate::environment::{draw::Canvas, Environment, Frame};
+
+use super::{bin, uniform};
+
+const MIN_POSITION: f64 = -1.2;
+const MAX_POSITION: f64 = 0.6;
+const MAX_SPEED: f64 = 0.07;
+const GOAL_POSITION: f64 = 0.5;
+const FORCE: f64 = 0.001;
+const GRAVITY: f64 = 0.0025;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MountainCarAction {
+    Left,
+    None,
+    Right,
+}
+
+pub struct MountainCarConfig {
+    /// 200 as in MountainCar-v0
+    pub max_steps: usize,
+    /// bins for each of position and velocity in `state`
+    pub bins: usize,
+}
+
+impl Default for MountainCarConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 200,
+            bins: 20,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MountainCar {
+    max_steps: usize,
+    bins: usize,
+    position: f64,
+    velocity: f64,
+}
+
+impl Default for MountainCar {
+    fn default() -> Self {
+        Self::new(MountainCarConfig::default())
+    }
+}
+
+impl MountainCar {
+    pub fn new(config: MountainCarConfig) -> Self {
+        Self {
+            max_steps: config.max_steps,
+            bins: config.bins.max(1),
+            position: -0.5,
+            velocity: 0.,
+        }
+    }
+
+    /// position and velocity
+    pub fn values(&self) -> [f64; 2] {
+        [self.position, self.velocity]
+    }
+}
+
+/// height of the hill at `position`
+fn height(position: f64) -> f64 {
+    (3. * position).sin() * 0.45 + 0.55
+}
+
+impl Environment<[usize; 2], MountainCarAction> for MountainCar {
+    /// bin of position and velocity
+    fn state(&self) -> [usize; 2] {
+        [
+            bin(self.position, MIN_POSITION, MAX_POSITION, self.bins),
+            bin(self.velocity, -MAX_SPEED, MAX_SPEED, self.bins),
+        ]
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.position = uniform(-0.6, -0.4);
+        self.velocity = 0.;
+    }
+
+    fn all_actions(&self) -> Vec<MountainCarAction> {
+        vec![
+            MountainCarAction::Left,
+            MountainCarAction::None,
+            MountainCarAction::Right,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &MountainCarAction) -> f64 {
+        let push = match action {
+            MountainCarAction::Left => -1.,
+            MountainCarAction::None => 0.,
+            MountainCarAction::Right => 1.,
+        };
+        self.velocity += push * FORCE - (3. * self.position).cos() * GRAVITY;
+        self.velocity = self.velocity.clamp(-MAX_SPEED, MAX_SPEED);
+        self.position = (self.position + self.velocity).clamp(MIN_POSITION, MAX_POSITION);
+        //stops at the left wall
+        if self.position == MIN_POSITION && self.velocity < 0. {
+            self.velocity = 0.;
+        }
+        -1.
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        self.terminated() || step >= self.max_steps
+    }
+
+    fn terminated(&self) -> bool {
+        self.position >= GOAL_POSITION && self.velocity >= 0.
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        let (width, height_px) = (150, 100);
+        let scale = width as f64 / (MAX_POSITION - MIN_POSITION);
+        let point = |position: f64| {
+            let x = (position - MIN_POSITION) * scale;
+            //flatter than the x scale, so the flag fits
+            let y = height_px as f64 - 5. - height(position) * 60.;
+            (x as i64, y as i64)
+        };
+        let mut canvas = Canvas::new(width, height_px, [255, 255, 255]);
+        for x in 0..width {
+            let from = MIN_POSITION + x as f64 / scale;
+            canvas.line(point(from), point(from + 1. / scale), [0, 0, 0]);
+        }
+        let flag = point(GOAL_POSITION);
+        canvas.line(flag, (flag.0, flag.1 - 15), [0, 0, 0]);
+        canvas.fill_rect(flag.0 as usize, (flag.1 - 15) as usize, 6, 4, [204, 204, 0]);
+        let car = point(self.position);
+        canvas.fill_rect(
+            (car.0 - 4).max(0) as usize,
+            (car.1 - 6).max(0) as usize,
+            8,
+            6,
+            [0, 0, 0],
+        );
+        canvas.into_frame()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        vec![self.position as f32, self.velocity as f32]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(env: &MountainCar, expected: [f64; 2]) {
+        let values = env.values();
+        assert!(
+            values
+                .iter()
+                .zip(expected)
+                .all(|(a, b)| (a - b).abs() < 1e-9),
+            "{values:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn step_matches_gymnasium() {
+        //from the MountainCar-v0 step equations, with the same starting values
+        let mut env = MountainCar::default();
+        assert_eq!(env.take_action_get_reward(&MountainCarAction::Right), -1.);
+        assert_close(&env, [-0.499176843004, 0.000823156996]);
+        (env.position, env.velocity) = (-0.3, 0.01);
+        env.take_action_get_reward(&MountainCarAction::Left);
+        assert_close(&env, [-0.292554024921, 0.007445975079]);
+        //hits the left wall and stops
+        (env.position, env.velocity) = (-1.19, -0.02);
+        env.take_action_get_reward(&MountainCarAction::Left);
+        assert_close(&env, [MIN_POSITION, 0.]);
+    }
+
+    #[test]
+    fn terminates_at_the_goal() {
+        let mut env = MountainCar::default();
+        for (position, velocity, terminated) in [
+            (GOAL_POSITION, 0., true),
+            (MAX_POSITION, 0.01, true),
+            (GOAL_POSITION - 1e-6, 0.01, false),
+            //rolling back down
+            (GOAL_POSITION, -0.01, false),
+        ] {
+            (env.position, env.velocity) = (position, velocity);
+            assert_eq!(env.terminated(), terminated, "{position} {velocity}");
+            assert_eq!(env.should_stop(1), terminated);
+        }
+        env.reset(1);
+        assert!(env.should_stop(200));
+        assert!(!env.terminated());
+    }
+}
@@ -0,0 +1,230 @@
+// Mouse
+// mouse eats 2, 4 or 10 cheese.
+// Finish on 10 cheese or -10 poison.
+// Max is 11 points
+//
+// |M | 2 | 0|
+// |4 |-10|10|
+
+use crate::environment::{Environment, Frame};
+
+use super::Point;
+
+/// The default map. `M` is where the mouse starts, `.` is empty, digits are cheese worth that many points
+/// which can be eaten once, `C` is the cheese which ends the epoch and `P` is poison which also ends it
+pub const MAP: &str = "\
+M2.
+4PC";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MouseState {
+    pub mouse: Point,
+    /// small cheese not eaten yet
+    pub cheese: Vec<Point>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MouseAction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone)]
+pub struct MouseRewards {
+    /// every move
+    pub step: f64,
+    /// moving off the map
+    pub invalid: f64,
+    pub poison: f64,
+    /// the cheese which ends the epoch
+    pub cheese: f64,
+}
+
+impl Default for MouseRewards {
+    fn default() -> Self {
+        Self {
+            step: -1.,
+            invalid: -5.,
+            poison: -10.,
+            cheese: 10.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MouseConfig {
+    /// drawn like `MAP`, with rows of the same length
+    pub map: String,
+    pub rewards: MouseRewards,
+    /// stops once the step count goes past this, so an epoch can be `max_steps` + 1 steps long
+    pub max_steps: usize,
+}
+
+impl Default for MouseConfig {
+    fn default() -> Self {
+        Self {
+            map: MAP.to_string(),
+            rewards: MouseRewards::default(),
+            max_steps: 100,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MouseEnvironment {
+    rewards: MouseRewards,
+    max_steps: usize,
+    /// each row of the map
+    map: Vec<Vec<char>>,
+    start: Point,
+    /// small cheese at the start of each epoch
+    cheese: Vec<Point>,
+    state: MouseState,
+    image: Vec<u8>,
+}
+
+impl Default for MouseEnvironment {
+    fn default() -> Self {
+        Self::new(MouseConfig::default())
+    }
+}
+
+impl MouseEnvironment {
+    /// Panics if there is no `M`, or the rows are not the same length
+    pub fn new(config: MouseConfig) -> Self {
+        let map: Vec<Vec<char>> = config
+            .map
+            .lines()
+            .map(|line| line.trim().chars().collect())
+            .filter(|row: &Vec<char>| !row.is_empty())
+            .collect();
+        assert!(
+            map.iter().all(|row| row.len() == map[0].len()),
+            "mouse map rows must be the same length"
+        );
+        let points = || {
+            map.iter().enumerate().flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(x, c)| (Point::new(x, y), *c))
+            })
+        };
+        let start = points()
+            .find(|p| p.1 == 'M')
+            .expect("mouse map needs an M")
+            .0;
+        let cheese: Vec<Point> = points()
+            .filter(|p| p.1.is_ascii_digit())
+            .map(|p| p.0)
+            .collect();
+        Self {
+            rewards: config.rewards,
+            max_steps: config.max_steps,
+            start,
+            state: MouseState {
+                mouse: start,
+                cheese: cheese.clone(),
+            },
+            cheese,
+            map,
+            image: vec![],
+        }
+    }
+
+    fn cell(&self, point: Point) -> char {
+        self.map[point.y][point.x]
+    }
+
+    fn save_image(&mut self) {
+        let width = self.map[0].len();
+        let mut pixels = vec![0u8; width * self.map.len() * 3];
+        let index = |p: Point| (p.y * width + p.x) * 3;
+
+        //brighter green for more points
+        for &p in &self.state.cheese {
+            let points = self.cell(p).to_digit(10).unwrap_or(0);
+            pixels[index(p) + 1] = (points * 45).min(255) as u8;
+        }
+        for (y, row) in self.map.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                match c {
+                    'C' => pixels[index(Point::new(x, y)) + 1] = 255,
+                    'P' => pixels[index(Point::new(x, y))] = 255,
+                    _ => {}
+                }
+            }
+        }
+        let mouse = index(self.state.mouse);
+        pixels[mouse..mouse + 3].copy_from_slice(&[100, 100, 100]);
+
+        self.image = pixels
+    }
+}
+
+impl Environment<MouseState, MouseAction> for MouseEnvironment {
+    fn state(&self) -> MouseState {
+        self.state.clone()
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.state = MouseState {
+            mouse: self.start,
+            cheese: self.cheese.clone(),
+        };
+    }
+
+    fn all_actions(&self) -> Vec<MouseAction> {
+        vec![
+            MouseAction::Up,
+            MouseAction::Down,
+            MouseAction::Left,
+            MouseAction::Right,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &MouseAction) -> f64 {
+        let (height, width) = (self.map.len(), self.map[0].len());
+        let mouse = &mut self.state.mouse;
+        let valid = match action {
+            MouseAction::Up => mouse.y > 0,
+            MouseAction::Down => mouse.y < height - 1,
+            MouseAction::Left => mouse.x > 0,
+            MouseAction::Right => mouse.x < width - 1,
+        };
+        if !valid {
+            return self.rewards.invalid;
+        }
+        match action {
+            MouseAction::Up => mouse.y -= 1,
+            MouseAction::Down => mouse.y += 1,
+            MouseAction::Left => mouse.x -= 1,
+            MouseAction::Right => mouse.x += 1,
+        }
+        let mouse = *mouse;
+
+        let mut reward = self.rewards.step;
+        if let Some(i) = self.state.cheese.iter().position(|&p| p == mouse) {
+            self.state.cheese.remove(i);
+            reward += self.cell(mouse).to_digit(10).unwrap_or(0) as f64;
+        }
+        reward += match self.cell(mouse) {
+            'P' => self.rewards.poison,
+            'C' => self.rewards.cheese,
+            _ => 0.,
+        };
+        reward
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        step > self.max_steps || matches!(self.cell(self.state.mouse), 'P' | 'C')
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.save_image();
+        Frame::rgb(self.map[0].len(), self.map.len(), &self.image)
+    }
+}
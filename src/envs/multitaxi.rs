@@ -0,0 +1,255 @@
+// Multi Taxi
+// Several taxis share the taxi map, each with its own passenger to drop off.
+// Taxis cannot drive into each other, and moves are made in order of the taxi's index.
+// A taxi is done once it drops off its passenger, and leaves the map.
+// End when all are done, or after `max_steps`
+
+use std::collections::HashMap;
+
+use crate::environment::{multiagent::MultiAgentEnvironment, Environment};
+
+use super::{
+    taxi::{TaxiAction, TaxiConfig, TaxiEnvironment, TaxiState},
+    Point,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiTaxiState {
+    pub taxi: TaxiState,
+    /// whether another taxi is in the cell above, below, left and right
+    pub nearby: [bool; 4],
+}
+
+#[derive(Clone)]
+pub struct MultiTaxiConfig {
+    pub taxis: usize,
+    /// map, rewards and max steps, as for a single taxi
+    pub taxi: TaxiConfig,
+    /// reward for driving into another taxi, which does not move
+    pub collision: f64,
+}
+
+impl Default for MultiTaxiConfig {
+    fn default() -> Self {
+        Self {
+            taxis: 3,
+            taxi: TaxiConfig::default(),
+            collision: -10.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiTaxi {
+    taxis: Vec<TaxiEnvironment>,
+    collision: f64,
+    max_steps: usize,
+}
+
+impl Default for MultiTaxi {
+    fn default() -> Self {
+        Self::new(MultiTaxiConfig::default())
+    }
+}
+
+impl MultiTaxi {
+    pub fn new(config: MultiTaxiConfig) -> Self {
+        let max_steps = config.taxi.max_steps;
+        let taxi = TaxiEnvironment::new(config.taxi);
+        Self {
+            taxis: vec![taxi; config.taxis],
+            collision: config.collision,
+            max_steps,
+        }
+    }
+
+    /// Whether a taxi other than `taxi` which is not done is at `point`
+    fn occupied(&self, taxi: usize, point: Point) -> bool {
+        (0..self.taxis.len())
+            .any(|i| i != taxi && !self.is_done(i) && self.taxis[i].state().taxi == point)
+    }
+}
+
+impl MultiAgentEnvironment<MultiTaxiState, TaxiAction> for MultiTaxi {
+    fn agents(&self) -> usize {
+        self.taxis.len()
+    }
+
+    fn state(&self, agent: usize) -> MultiTaxiState {
+        let taxi = self.taxis[agent].state();
+        let Point { x, y } = taxi.taxi;
+        let nearby = [
+            y > 0 && self.occupied(agent, Point::new(x, y - 1)),
+            self.occupied(agent, Point::new(x, y + 1)),
+            x > 0 && self.occupied(agent, Point::new(x - 1, y)),
+            self.occupied(agent, Point::new(x + 1, y)),
+        ];
+        MultiTaxiState { taxi, nearby }
+    }
+
+    /// Every taxi starts in a different cell
+    fn reset(&mut self, epoch: usize) {
+        for i in 0..self.taxis.len() {
+            loop {
+                self.taxis[i].reset(epoch);
+                let start = self.taxis[i].state().taxi;
+                if !self.taxis[..i].iter().any(|t| t.state().taxi == start) {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn all_actions(&self) -> Vec<TaxiAction> {
+        self.taxis[0].all_actions()
+    }
+
+    fn take_actions_get_rewards(&mut self, actions: &HashMap<usize, TaxiAction>) -> Vec<f64> {
+        let mut rewards = vec![0.; self.taxis.len()];
+        for (i, reward) in rewards.iter_mut().enumerate() {
+            let Some(action) = actions.get(&i).filter(|_| !self.is_done(i)) else {
+                continue;
+            };
+            let next = self.taxis[i].next_taxi(action);
+            *reward = if next != self.taxis[i].state().taxi && self.occupied(i, next) {
+                self.collision
+            } else {
+                self.taxis[i].take_action_get_reward(action)
+            };
+        }
+        rewards
+    }
+
+    /// Dropped off its passenger
+    fn is_done(&self, agent: usize) -> bool {
+        let state = self.taxis[agent].state();
+        state.passenger == state.dropoff && !state.in_taxi
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        step >= self.max_steps || (0..self.taxis.len()).all(|i| self.is_done(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TaxiAction::*;
+
+    /// Default map with a taxi at each of `taxis`, picking up from R and dropping off at G.
+    /// Collisions are -5, so they can be told apart from walls
+    fn multitaxi(taxis: &[(usize, usize)]) -> MultiTaxi {
+        let mut env = MultiTaxi::new(MultiTaxiConfig {
+            taxis: taxis.len(),
+            collision: -5.,
+            ..Default::default()
+        });
+        for (taxi, &(x, y)) in env.taxis.iter_mut().zip(taxis) {
+            taxi.state = TaxiState {
+                taxi: Point::new(x, y),
+                dropoff: Point::new(4, 0),
+                passenger: Point::new(0, 0),
+                in_taxi: false,
+            };
+        }
+        env
+    }
+
+    fn position(env: &MultiTaxi, taxi: usize) -> Point {
+        env.state(taxi).taxi.taxi
+    }
+
+    #[test]
+    fn driving_into_a_taxi_is_a_collision() {
+        let mut env = multitaxi(&[(1, 1), (2, 1), (0, 3)]);
+        assert_eq!(env.state(0).nearby, [false, false, false, true]);
+        assert_eq!(env.state(1).nearby, [false, false, true, false]);
+
+        let actions = HashMap::from([(0, Right), (2, Up)]);
+        assert_eq!(env.take_actions_get_rewards(&actions), vec![-5., 0., -1.]);
+        assert_eq!(position(&env, 0), Point::new(1, 1));
+        assert_eq!(position(&env, 2), Point::new(0, 2));
+    }
+
+    #[test]
+    fn taxis_move_in_order() {
+        //the first taxi hits the second before it moves away
+        let mut env = multitaxi(&[(1, 1), (2, 1)]);
+        let actions = HashMap::from([(0, Right), (1, Right)]);
+        assert_eq!(env.take_actions_get_rewards(&actions), vec![-5., -1.]);
+        assert_eq!(position(&env, 0), Point::new(1, 1));
+        assert_eq!(position(&env, 1), Point::new(3, 1));
+
+        //the second can follow into the cell the first left
+        let mut env = multitaxi(&[(2, 1), (1, 1)]);
+        assert_eq!(env.take_actions_get_rewards(&actions), vec![-1., -1.]);
+        assert_eq!(position(&env, 1), Point::new(2, 1));
+    }
+
+    #[test]
+    fn walls_are_not_collisions() {
+        let mut env = multitaxi(&[(1, 0), (2, 0)]);
+        let rewards = env.take_actions_get_rewards(&HashMap::from([(0, Right)]));
+        assert_eq!(rewards, vec![-10., 0.]);
+        //staying put, even next to a taxi
+        let rewards = env.take_actions_get_rewards(&HashMap::from([(1, Left), (0, Pickup)]));
+        assert_eq!(rewards, vec![-10., -10.]);
+    }
+
+    #[test]
+    fn each_taxi_picks_up_its_passenger() {
+        let mut env = multitaxi(&[(0, 0), (0, 1)]);
+        let rewards = env.take_actions_get_rewards(&HashMap::from([(0, Pickup), (1, Pickup)]));
+        assert_eq!(rewards, vec![10., -10.]);
+        assert!(env.state(0).taxi.in_taxi);
+        assert!(!env.state(1).taxi.in_taxi);
+    }
+
+    #[test]
+    fn done_taxis_leave_the_map() {
+        let mut env = multitaxi(&[(4, 0), (4, 1)]);
+        env.taxis[0].state.in_taxi = true;
+        env.taxis[0].state.passenger = Point::new(4, 0);
+        assert_eq!(env.state(1).nearby, [true, false, false, false]);
+
+        let rewards = env.take_actions_get_rewards(&HashMap::from([(0, Dropoff)]));
+        assert_eq!(rewards, vec![20., 0.]);
+        assert!(env.is_done(0));
+        assert!(!env.is_done(1));
+        assert!(!env.should_stop(1));
+        assert_eq!(env.state(1).nearby, [false; 4]);
+
+        //ignored once done, and no longer in the way
+        let rewards = env.take_actions_get_rewards(&HashMap::from([(0, Down), (1, Up)]));
+        assert_eq!(rewards, vec![0., -1.]);
+        assert_eq!(position(&env, 1), Point::new(4, 0));
+    }
+
+    #[test]
+    fn stops_when_all_are_done_or_out_of_steps() {
+        let mut env = multitaxi(&[(4, 0), (4, 1)]);
+        assert!(!env.should_stop(99));
+        assert!(env.should_stop(100));
+        for taxi in &mut env.taxis {
+            taxi.state.passenger = Point::new(4, 0);
+        }
+        assert!(env.should_stop(1));
+    }
+
+    #[test]
+    fn taxis_start_apart() {
+        fastrand::seed(1);
+        let mut env = MultiTaxi::new(MultiTaxiConfig {
+            taxis: 20,
+            ..Default::default()
+        });
+        for epoch in 0..20 {
+            env.reset(epoch);
+            let mut starts: Vec<Point> = (0..20).map(|i| position(&env, i)).collect();
+            starts.sort_by_key(|p| (p.x, p.y));
+            starts.dedup();
+            assert_eq!(starts.len(), 20);
+        }
+    }
+}
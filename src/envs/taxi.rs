@@ -0,0 +1,450 @@
+// Taxi based on OpenAi Gym
+// Taxi must pick up from one of the lettered locations and drop off at one of the others
+// Dont drive into walls
+// End if drop off at the correct location, or once past `max_steps`
+// +---------+
+// |R: | : :G|
+// | : : : : |
+// | : : : : |
+// | | : | : |
+// |Y| : |B: |
+// +---------+
+
+use crate::environment::{Environment, Frame};
+
+use super::Point;
+
+// We need a few things to run
+//1. State Struct = TaxiState
+//2. Action (normally enum) = TaxiAction
+//3. Environment Struct that implements the Environment trait = TaxiEnvironment
+
+//4. the Algorithm, see the taxi example
+//in this case:
+// - QLearning
+// - with exploration strategy where random selection decreases towards experience
+
+/// The default map. Letters are locations, and `|` are walls between cells
+pub const MAP: &str = "\
++---------+
+|R: | : :G|
+| : : : : |
+| : : : : |
+| | : | : |
+|Y| : |B: |
++---------+";
+
+/// Step 1. State - must impl Hash, Eq, PartialEq, Clone
+/// This is what gets saved to QTable, so make sure it is small set of states
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaxiState {
+    pub taxi: Point,
+    pub dropoff: Point,
+    /// same as `taxi` while in the taxi
+    pub passenger: Point,
+    pub in_taxi: bool,
+}
+
+/// Step 2. Action - must impl Hash, Eq, PartialEq, Clone
+/// These are actions that can be performed
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TaxiAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Dropoff,
+    Pickup,
+}
+
+#[derive(Clone)]
+pub struct TaxiRewards {
+    /// every move
+    pub step: f64,
+    /// driving into a wall, or picking up or dropping off in the wrong place
+    pub invalid: f64,
+    pub pickup: f64,
+    pub dropoff: f64,
+}
+
+impl Default for TaxiRewards {
+    fn default() -> Self {
+        Self {
+            step: -1.,
+            invalid: -10.,
+            pickup: 10.,
+            dropoff: 20.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TaxiConfig {
+    /// drawn like `MAP`, with at least 2 lettered locations
+    pub map: String,
+    pub rewards: TaxiRewards,
+    /// stops once the step count goes past this, so an epoch can be `max_steps` + 1 steps long
+    pub max_steps: usize,
+}
+
+impl Default for TaxiConfig {
+    fn default() -> Self {
+        Self {
+            map: MAP.to_string(),
+            rewards: TaxiRewards::default(),
+            max_steps: 100,
+        }
+    }
+}
+
+/// Step 3. Environment
+/// This is the critical part that acts and communicates with the agent
+/// You just need to implement the required traits in Environment correctly
+/// Optionally add some helper methods to make things easier
+///
+/// -`reset` is called at the BEGINNING of each epoch
+/// - Agent will pick an action for us
+/// - we then use the action in `take_action_get_reward` step and return the reward
+/// - we then tell agent if we should stop or not
+#[derive(Clone)]
+pub struct TaxiEnvironment {
+    rewards: TaxiRewards,
+    max_steps: usize,
+    width: usize,
+    height: usize,
+    /// letter of each location
+    locations: Vec<(char, Point)>,
+    /// wall to the right of [y][x]
+    walls: Vec<Vec<bool>>,
+    /// set directly by `MultiTaxi` tests
+    pub(super) state: TaxiState,
+    pixels: Vec<u8>,
+}
+
+impl Default for TaxiEnvironment {
+    fn default() -> Self {
+        Self::new(TaxiConfig::default())
+    }
+}
+
+impl TaxiEnvironment {
+    /// Panics if the map has fewer than 2 locations
+    pub fn new(config: TaxiConfig) -> Self {
+        let rows: Vec<Vec<char>> = config
+            .map
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| line.starts_with('|'))
+            .map(|line| line.chars().collect())
+            .collect();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| (row.len() - 1) / 2);
+        let mut locations = vec![];
+        let mut walls = vec![vec![false; width]; height];
+        for (y, (row, walls)) in rows.iter().zip(&mut walls).enumerate() {
+            for (x, wall) in walls.iter_mut().enumerate() {
+                let cell = row.get(1 + 2 * x).copied().unwrap_or(' ');
+                if cell.is_alphabetic() {
+                    locations.push((cell, Point::new(x, y)));
+                }
+                //the last | is the edge of the map
+                *wall = x < width - 1 && row.get(2 + 2 * x) == Some(&'|');
+            }
+        }
+        assert!(locations.len() >= 2, "taxi map needs at least 2 locations");
+
+        let mut env = Self {
+            rewards: config.rewards,
+            max_steps: config.max_steps,
+            width,
+            height,
+            locations,
+            walls,
+            state: TaxiState {
+                taxi: Point::default(),
+                dropoff: Point::default(),
+                passenger: Point::default(),
+                in_taxi: false,
+            },
+            pixels: vec![],
+        };
+        env.reset(0);
+        env
+    }
+
+    fn is_finished(&self) -> bool {
+        let state = &self.state;
+        state.passenger == state.dropoff && !state.in_taxi
+    }
+
+    /// Whether we can drive from `from` in the direction of `action`
+    fn can_move(&self, from: Point, action: &TaxiAction) -> bool {
+        let Point { x, y } = from;
+        match action {
+            TaxiAction::Up => y > 0,
+            TaxiAction::Down => y < self.height - 1,
+            TaxiAction::Left => x > 0 && !self.walls[y][x - 1],
+            TaxiAction::Right => x < self.width - 1 && !self.walls[y][x],
+            TaxiAction::Dropoff | TaxiAction::Pickup => false,
+        }
+    }
+
+    /// Where the taxi would be after `action`, used by `MultiTaxi` to stop taxis driving into each other
+    pub(super) fn next_taxi(&self, action: &TaxiAction) -> Point {
+        let Point { x, y } = self.state.taxi;
+        match action {
+            _ if !self.can_move(self.state.taxi, action) => self.state.taxi,
+            TaxiAction::Up => Point::new(x, y - 1),
+            TaxiAction::Down => Point::new(x, y + 1),
+            TaxiAction::Left => Point::new(x - 1, y),
+            TaxiAction::Right => Point::new(x + 1, y),
+            TaxiAction::Dropoff | TaxiAction::Pickup => self.state.taxi,
+        }
+    }
+
+    ///board is w x h, however we want to show walls in between also, so we make it 2w x h
+    fn save_image(&mut self) {
+        let width = self.width * 2;
+        let mut pixels = vec![0u8; width * self.height * 3];
+        let index = |x: usize, y: usize| (y * width + x) * 3;
+
+        //walls
+        for (y, row) in self.walls.iter().enumerate() {
+            for (x, &wall) in row.iter().enumerate() {
+                if wall {
+                    pixels[index(x * 2 + 1, y) + 2] = 50;
+                }
+            }
+        }
+
+        let state = &self.state;
+        //pass+drop
+        pixels[index(state.passenger.x * 2, state.passenger.y) + 1] = 255; //green
+        pixels[index(state.dropoff.x * 2, state.dropoff.y)] = 255; //red
+
+        //yellow if inside else magenta
+        let taxi = index(state.taxi.x * 2, state.taxi.y);
+        pixels[taxi] = 255;
+        pixels[taxi + if state.in_taxi { 1 } else { 2 }] = 255;
+        self.pixels = pixels;
+    }
+}
+
+//3.1 implement the `Environment` trait for the struct
+impl Environment<TaxiState, TaxiAction> for TaxiEnvironment {
+    fn state(&self) -> TaxiState {
+        self.state.clone()
+    }
+
+    /// Passenger and dropoff at random different locations, and taxi anywhere
+    fn reset(&mut self, _epoch: usize) {
+        let passenger = fastrand::usize(0..self.locations.len());
+        let dropoff = loop {
+            let temp = fastrand::usize(0..self.locations.len());
+            if temp != passenger {
+                break temp;
+            }
+        };
+        self.state = TaxiState {
+            taxi: Point::new(
+                fastrand::usize(0..self.width),
+                fastrand::usize(0..self.height),
+            ),
+            dropoff: self.locations[dropoff].1,
+            passenger: self.locations[passenger].1,
+            in_taxi: false,
+        };
+    }
+
+    fn all_actions(&self) -> Vec<TaxiAction> {
+        vec![
+            TaxiAction::Up,
+            TaxiAction::Down,
+            TaxiAction::Left,
+            TaxiAction::Right,
+            TaxiAction::Dropoff,
+            TaxiAction::Pickup,
+        ]
+    }
+
+    fn take_action_get_reward(&mut self, action: &TaxiAction) -> f64 {
+        let can_move = self.can_move(self.state.taxi, action);
+        let rewards = &self.rewards;
+        let state = &mut self.state;
+        let reward = match action {
+            TaxiAction::Dropoff if state.in_taxi && state.taxi == state.dropoff => {
+                state.in_taxi = false;
+                rewards.dropoff
+            }
+            TaxiAction::Pickup if !state.in_taxi && state.taxi == state.passenger => {
+                state.in_taxi = true;
+                rewards.pickup
+            }
+            //walls, or invalid pickup or dropoff
+            _ if !can_move => rewards.invalid,
+            TaxiAction::Up => {
+                state.taxi.y -= 1;
+                rewards.step
+            }
+            TaxiAction::Down => {
+                state.taxi.y += 1;
+                rewards.step
+            }
+            TaxiAction::Left => {
+                state.taxi.x -= 1;
+                rewards.step
+            }
+            TaxiAction::Right => {
+                state.taxi.x += 1;
+                rewards.step
+            }
+            TaxiAction::Dropoff | TaxiAction::Pickup => rewards.invalid,
+        };
+        //passenger gets taxi coords if in taxi
+        if state.in_taxi {
+            state.passenger = state.taxi;
+        }
+        reward
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        step > self.max_steps || self.is_finished()
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.save_image();
+        Frame::rgb(self.width * 2, self.height, &self.pixels)
+    }
+
+    ///passenger is green, dropoff red, and taxi magenta (yellow with passenger)
+    fn render_text(&self) -> String {
+        let state = &self.state;
+        let border = format!("+{}+", "-".repeat(self.width * 2 - 1));
+        let mut text = format!("{border}\n");
+        for y in 0..self.height {
+            text.push('|');
+            for x in 0..self.width {
+                let point = Point::new(x, y);
+                let letter = self
+                    .locations
+                    .iter()
+                    .find(|l| l.1 == point)
+                    .map_or(' ', |l| l.0);
+                if state.taxi == point {
+                    text.push_str(if state.in_taxi {
+                        "\x1b[43m"
+                    } else {
+                        "\x1b[45m"
+                    });
+                }
+                if !state.in_taxi && state.passenger == point {
+                    text.push_str("\x1b[1;32m");
+                } else if state.dropoff == point {
+                    text.push_str("\x1b[1;31m");
+                }
+                text.push(letter);
+                text.push_str("\x1b[0m");
+                if x < self.width - 1 {
+                    text.push(if self.walls[y][x] { '|' } else { ':' });
+                }
+            }
+            text.push_str("|\n");
+        }
+        text.push_str(&border);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Default map with the passenger at R, dropoff at G and the taxi at `x`,`y`
+    fn taxi(x: usize, y: usize) -> TaxiEnvironment {
+        TaxiEnvironment {
+            state: TaxiState {
+                taxi: Point::new(x, y),
+                dropoff: Point::new(4, 0),
+                passenger: Point::new(0, 0),
+                in_taxi: false,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn walls_and_edges_block_moves() {
+        for (x, y, action) in [
+            (1, 0, TaxiAction::Right),
+            (2, 0, TaxiAction::Left),
+            (0, 3, TaxiAction::Right),
+            (3, 4, TaxiAction::Left),
+            (2, 0, TaxiAction::Up),
+            (2, 4, TaxiAction::Down),
+            (4, 2, TaxiAction::Right),
+            (0, 2, TaxiAction::Left),
+        ] {
+            let mut env = taxi(x, y);
+            assert_eq!(
+                env.take_action_get_reward(&action),
+                -10.,
+                "{x},{y} {action:?}"
+            );
+            assert_eq!(env.state.taxi, Point::new(x, y));
+        }
+        let mut env = taxi(1, 1);
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Right), -1.);
+        assert_eq!(env.state.taxi, Point::new(2, 1));
+    }
+
+    #[test]
+    fn picks_up_and_drops_off() {
+        let mut env = taxi(0, 0);
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Pickup), 10.);
+        assert!(env.state.in_taxi);
+        env.take_action_get_reward(&TaxiAction::Down);
+        assert_eq!(env.state.passenger, Point::new(0, 1));
+        assert!(!env.should_stop(1));
+
+        use TaxiAction::{Right, Up};
+        for action in [Right, Right, Right, Right, Up] {
+            assert_eq!(env.take_action_get_reward(&action), -1.);
+        }
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Dropoff), 20.);
+        assert!(!env.state.in_taxi);
+        assert_eq!(env.state.passenger, Point::new(4, 0));
+        assert!(env.should_stop(1));
+    }
+
+    #[test]
+    fn wrong_pickup_and_dropoff_are_invalid() {
+        //no passenger here
+        let mut env = taxi(1, 0);
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Pickup), -10.);
+        assert!(!env.state.in_taxi);
+        //no passenger to drop off
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Dropoff), -10.);
+
+        let mut env = taxi(0, 0);
+        env.take_action_get_reward(&TaxiAction::Pickup);
+        //already in the taxi
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Pickup), -10.);
+        //not the dropoff
+        assert_eq!(env.take_action_get_reward(&TaxiAction::Dropoff), -10.);
+        assert!(env.state.in_taxi);
+        assert!(!env.should_stop(1));
+    }
+
+    #[test]
+    fn stops_once_past_max_steps() {
+        let mut env = TaxiEnvironment::new(TaxiConfig {
+            max_steps: 5,
+            ..Default::default()
+        });
+        env.state = taxi(2, 2).state;
+        assert!(!env.should_stop(5));
+        assert!(env.should_stop(6));
+    }
+}
@@ -0,0 +1,89 @@
+// Tic-Tac-Toe
+// 2 players take turns to place their mark on a 3x3 board. 3 in a row wins, and a full board is a draw.
+// Placing on a taken cell, or a cell which is not on the board, loses the game, so agents learn which moves are valid
+//
+//  X O .
+//  . X .
+//  O . X
+
+use crate::environment::{turnbased::TurnBasedEnvironment, Frame};
+
+use super::{board::Board, GameRewards, Mark};
+
+/// State is the 9 cells row by row, as seen by the current player. Actions are the index of a cell
+#[derive(Clone)]
+pub struct TicTacToe {
+    rewards: GameRewards,
+    board: Board,
+}
+
+impl Default for TicTacToe {
+    fn default() -> Self {
+        Self::new(GameRewards::default())
+    }
+}
+
+impl TicTacToe {
+    pub fn new(rewards: GameRewards) -> Self {
+        Self {
+            rewards,
+            board: Board::new(3, 3, 3),
+        }
+    }
+}
+
+impl TurnBasedEnvironment<Vec<Mark>, usize> for TicTacToe {
+    fn state(&self) -> Vec<Mark> {
+        self.board.marks()
+    }
+
+    fn reset(&mut self, _epoch: usize) {
+        self.board.reset();
+    }
+
+    fn current_player(&self) -> usize {
+        self.board.current
+    }
+
+    fn all_actions(&self) -> Vec<usize> {
+        (0..9).collect()
+    }
+
+    fn valid_actions(&self) -> Vec<usize> {
+        (0..9).filter(|&i| self.board.cells[i].is_none()).collect()
+    }
+
+    fn take_action_get_rewards(&mut self, action: &usize) -> Vec<f64> {
+        self.board.play(Some(*action), &self.rewards)
+    }
+
+    fn is_over(&self) -> bool {
+        self.board.over
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        Frame::rgb(3, 3, self.board.image())
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.board.observation()
+    }
+
+    fn render_text(&self) -> String {
+        self.board.render_text("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn off_the_board_is_an_invalid_move() {
+        let mut game = TicTacToe::default();
+        game.take_action_get_rewards(&4);
+        let rewards = game.take_action_get_rewards(&9);
+        assert_eq!(rewards, vec![0., GameRewards::default().invalid]);
+        assert!(game.is_over());
+    }
+}
@@ -1,8 +1,13 @@
 pub mod agent;
 pub mod environment;
+pub mod envs;
+#[cfg(feature = "serde")]
+pub mod persist;
 mod progress;
+mod random;
 pub mod runner;
 pub mod strategy;
+pub mod sweep;
 
 //See examples for details
 //run with Runner::run()
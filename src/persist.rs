@@ -0,0 +1,274 @@
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Version of the saved file layout.
+/// Increase this when the layout changes, so older files are detected as incompatible
+pub const VERSION: u32 = 1;
+const MAGIC: &str = "learnwell";
+/// Most bytes read by `from_reader` in binary format, so a damaged file cannot ask for a huge allocation
+pub const MAX_BYTES: u64 = 1 << 32;
+/// Headers are small, so a file which is not ours fails quickly
+const MAX_HEADER_BYTES: u64 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// compact, use for large qtables
+    Binary,
+    /// human readable
+    Json,
+}
+
+/// Written at the start of every file so we can check it before reading the rest
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Header {
+    pub magic: String,
+    pub version: u32,
+    /// what was saved e.g. "qlearning"
+    pub kind: String,
+}
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(std::io::Error),
+    /// file could not be encoded or decoded
+    Format(String),
+    /// file was saved with a different `VERSION`
+    Version {
+        found: u32,
+        expected: u32,
+    },
+    /// file is not a learnwell file, or holds a different kind of data
+    Kind {
+        found: String,
+        expected: String,
+    },
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistError::Io(e) => write!(f, "io error: {e}"),
+            PersistError::Format(e) => write!(f, "format error: {e}"),
+            PersistError::Version { found, expected } => {
+                write!(f, "incompatible version {found}, expected {expected}")
+            }
+            PersistError::Kind { found, expected } => {
+                write!(f, "file contains {found}, expected {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<std::io::Error> for PersistError {
+    fn from(e: std::io::Error) -> Self {
+        PersistError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for PersistError {
+    fn from(e: bincode::Error) -> Self {
+        PersistError::Format(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(e: serde_json::Error) -> Self {
+        PersistError::Format(e.to_string())
+    }
+}
+
+impl Header {
+    fn new(kind: &str) -> Self {
+        Self {
+            magic: MAGIC.to_string(),
+            version: VERSION,
+            kind: kind.to_string(),
+        }
+    }
+
+    fn check(&self, kind: &str) -> Result<(), PersistError> {
+        if self.magic != MAGIC {
+            return Err(PersistError::Kind {
+                found: self.magic.clone(),
+                expected: MAGIC.to_string(),
+            });
+        }
+        if self.version != VERSION {
+            return Err(PersistError::Version {
+                found: self.version,
+                expected: VERSION,
+            });
+        }
+        if self.kind != kind {
+            return Err(PersistError::Kind {
+                found: self.kind.clone(),
+                expected: kind.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonFile<T> {
+    header: Header,
+    data: T,
+}
+
+/// Writes header followed by `data`
+pub fn to_writer<T: Serialize>(
+    writer: impl Write,
+    format: Format,
+    kind: &str,
+    data: &T,
+) -> Result<(), PersistError> {
+    let header = Header::new(kind);
+    match format {
+        Format::Binary => {
+            let mut writer = writer;
+            bincode::serialize_into(&mut writer, &header)?;
+            bincode::serialize_into(&mut writer, data)?;
+        }
+        Format::Json => serde_json::to_writer(writer, &JsonFile { header, data })?,
+    }
+    Ok(())
+}
+
+/// Reads and checks the header, then reads the data.
+/// Binary data bigger than `MAX_BYTES` is an error
+pub fn from_reader<T: DeserializeOwned>(
+    reader: impl Read,
+    format: Format,
+    kind: &str,
+) -> Result<T, PersistError> {
+    read(reader, format, kind, MAX_BYTES)
+}
+
+/// As `from_reader`, reading at most `limit` bytes of binary data
+fn read<T: DeserializeOwned>(
+    reader: impl Read,
+    format: Format,
+    kind: &str,
+    limit: u64,
+) -> Result<T, PersistError> {
+    match format {
+        Format::Binary => {
+            let mut reader = reader;
+            let header: Header = binary(MAX_HEADER_BYTES).deserialize_from(&mut reader)?;
+            header.check(kind)?;
+            Ok(binary(limit).deserialize_from(&mut reader)?)
+        }
+        Format::Json => {
+            //check header before we try read the data, so we get a meaningful error
+            let file: JsonFile<serde_json::Value> = serde_json::from_reader(reader)?;
+            file.header.check(kind)?;
+            Ok(serde_json::from_value(file.data)?)
+        }
+    }
+}
+
+pub fn save<T: Serialize>(
+    path: impl AsRef<Path>,
+    format: Format,
+    kind: &str,
+    data: &T,
+) -> Result<(), PersistError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    to_writer(&mut writer, format, kind, data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a file saved with `save`. Binary data can be no bigger than the file
+pub fn load<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    format: Format,
+    kind: &str,
+) -> Result<T, PersistError> {
+    let file = File::open(path)?;
+    let limit = file.metadata()?.len();
+    read(BufReader::new(file), format, kind, limit)
+}
+
+/// Same encoding as `bincode::serialize_into`, which writes the files, but with a limit on bytes read
+fn binary(limit: u64) -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file written by a later version of the layout
+    fn future_file(format: Format) -> Vec<u8> {
+        let header = Header {
+            version: VERSION + 1,
+            ..Header::new("qlearning")
+        };
+        let mut bytes = vec![];
+        match format {
+            Format::Binary => {
+                bincode::serialize_into(&mut bytes, &header).unwrap();
+                bincode::serialize_into(&mut bytes, &1u32).unwrap();
+            }
+            Format::Json => {
+                serde_json::to_writer(&mut bytes, &JsonFile { header, data: 1u32 }).unwrap()
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn round_trips_data() {
+        for format in [Format::Binary, Format::Json] {
+            let mut bytes = vec![];
+            to_writer(&mut bytes, format, "qlearning", &vec![(1u8, 0.5)]).unwrap();
+            let data: Vec<(u8, f64)> = from_reader(bytes.as_slice(), format, "qlearning").unwrap();
+            assert_eq!(data, vec![(1, 0.5)]);
+        }
+    }
+
+    #[test]
+    fn other_version_is_an_error() {
+        for format in [Format::Binary, Format::Json] {
+            let result: Result<u32, _> =
+                from_reader(future_file(format).as_slice(), format, "qlearning");
+            assert!(matches!(
+                result,
+                Err(PersistError::Version { found, expected }) if found == VERSION + 1 && expected == VERSION
+            ));
+        }
+    }
+
+    #[test]
+    fn other_kind_is_an_error() {
+        for format in [Format::Binary, Format::Json] {
+            let mut bytes = vec![];
+            to_writer(&mut bytes, format, "deepqlearning", &1u32).unwrap();
+            let result: Result<u32, _> = from_reader(bytes.as_slice(), format, "qlearning");
+            assert!(
+                matches!(result, Err(PersistError::Kind { found, .. }) if found == "deepqlearning")
+            );
+        }
+    }
+
+    #[test]
+    fn other_file_is_an_error() {
+        for format in [Format::Binary, Format::Json] {
+            let result: Result<u32, _> =
+                from_reader(&b"not a learnwell file"[..], format, "qlearning");
+            assert!(result.is_err());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Progress {
     pub epoch: usize,
     pub epoch_step: usize,
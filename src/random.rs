@@ -0,0 +1,36 @@
+//! Sampling from distributions with `fastrand`, so random state can be seeded and checkpointed
+
+/// Normal distribution, using the Box-Muller transform
+pub(crate) fn normal(mean: f64, std: f64) -> f64 {
+    let u1 = 1. - fastrand::f64(); //avoid ln(0)
+    let u2 = fastrand::f64();
+    mean + std * (-2. * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// Gamma distribution with scale 1, using Marsaglia and Tsang's method
+pub(crate) fn gamma(shape: f64) -> f64 {
+    if shape < 1. {
+        //boost, then scale back down
+        return gamma(shape + 1.) * (1. - fastrand::f64()).powf(1. / shape);
+    }
+    let d = shape - 1. / 3.;
+    let c = 1. / (9. * d).sqrt();
+    loop {
+        let x = normal(0., 1.);
+        let v = (1. + c * x).powi(3);
+        if v <= 0. {
+            continue;
+        }
+        let u = 1. - fastrand::f64();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// Beta distribution, from 2 gamma samples
+pub(crate) fn beta(a: f64, b: f64) -> f64 {
+    let x = gamma(a);
+    let y = gamma(b);
+    x / (x + y)
+}
@@ -1,97 +1,116 @@
-use std::time::{Duration, Instant};
+use crate::{agent::Agent, environment::Environment, progress::Progress};
+use metrics::Tracked;
 
-use show_image::{create_window, run_context};
+#[cfg(feature = "serde")]
+mod checkpoint;
+#[cfg(feature = "display")]
+mod display;
+mod evaluate;
+mod metrics;
+mod multiagent;
+mod parallel;
+mod plot;
+#[cfg(feature = "record")]
+mod record;
+mod selfplay;
+mod text;
+mod vecenv;
 
-use crate::{agent::Agent, environment::Environment, progress::Progress};
+#[cfg(feature = "serde")]
+pub use checkpoint::CheckpointConfig;
+#[cfg(feature = "display")]
+pub use display::{DisplayConfig, Palette};
+pub use metrics::Metrics;
+pub use parallel::ParallelConfig;
+#[cfg(feature = "record")]
+pub use record::{RecordConfig, RecordEpochs, RecordFormat};
+pub use selfplay::SelfPlayConfig;
+pub use text::TextConfig;
 
 pub struct Runner;
 
 impl Runner {
-    pub fn run<S, A>(
-        mut agent: impl Agent<S, A>,
+    /// Runs for `epochs` and returns the trained agent
+    pub fn run<S, A, AG: Agent<S, A>>(
+        mut agent: AG,
         mut environment: impl Environment<S, A>,
         epochs: usize,
-    ) {
+    ) -> AG {
         let mut progress: Progress = Progress {
             epoch: 0,
             epoch_step: 0,
             cumulative_steps: 0,
         };
         for epoch in 1..=epochs {
-            environment.reset(epoch);
-            progress.epoch_step = 0;
-            let mut done = false;
-            while !done {
-                progress.epoch = epoch;
-                progress.epoch_step += 1;
-                progress.cumulative_steps += 1;
-                done = agent.step(progress, &mut environment);
-            }
+            Self::run_epoch(
+                &mut agent,
+                &mut environment,
+                &mut progress,
+                epoch,
+                &mut |_, _, _| {},
+            );
         }
+        agent
     }
 
-    pub fn run_with_display<S: Send + 'static, A>(
-        mut agent: impl Agent<S, A> + Send + 'static,
-        mut environment: impl Environment<S, A> + Send + 'static,
+    /// Same as `run`, but also returns the return and length of every epoch
+    pub fn run_with_metrics<S, A, AG: Agent<S, A>>(
+        mut agent: AG,
+        mut environment: impl Environment<S, A>,
         epochs: usize,
-        config: DisplayConfig,
-    ) -> ! {
-        let spf = 1.0 / config.fps as f32;
-        let sta = Instant::now();
-        let mut last_render = Instant::now();
-        run_context(move || {
-            let window = create_window("Learnwell", Default::default()).unwrap();
-
-            let mut progress: Progress = Progress {
-                epoch: 0,
-                epoch_step: 0,
-                cumulative_steps: 0,
-            };
-            for epoch in 1..=epochs {
-                environment.reset(epoch);
-                progress.epoch_step = 0;
-                let mut done = false;
-                while !done {
-                    progress.epoch = epoch;
-                    progress.epoch_step += 1;
-                    progress.cumulative_steps += 1;
-                    done = agent.step(progress, &mut environment);
-
-                    let elapsed = last_render.elapsed().as_secs_f32();
-                    if elapsed > spf {
-                        last_render = Instant::now();
-                        let image = environment.get_image();
-                        let _ = window.set_image("im1", image);
-                    }
-
-                    if progress.epoch > config.step_time_start && !config.step_time.is_zero() {
-                        std::thread::sleep(config.step_time)
-                    }
-                }
-            }
-            println!("Done in {:.2}s", sta.elapsed().as_secs_f64());
-            window.wait_until_destroyed()
-        });
+    ) -> (AG, Metrics) {
+        let mut metrics = Metrics::default();
+        let mut progress: Progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        Self::run_until(
+            &mut agent,
+            &mut environment,
+            epochs,
+            &mut progress,
+            &mut metrics,
+        );
+        (agent, metrics)
     }
-}
 
-pub struct DisplayConfig {
-    /// how many fps to show
-    pub fps: usize,
-    /// slow down each step to take at least this duration.
-    /// Use 0 for no slowdown
-    /// Useful to show learning
-    pub step_time: Duration,
-    /// which epoch to slow down from
-    pub step_time_start: usize,
-}
+    /// Continues from `progress` until `epochs`, adding to `metrics`
+    pub(crate) fn run_until<S, A>(
+        agent: &mut impl Agent<S, A>,
+        environment: &mut impl Environment<S, A>,
+        epochs: usize,
+        progress: &mut Progress,
+        metrics: &mut Metrics,
+    ) {
+        let sta = std::time::Instant::now();
+        let mut environment = Tracked::new(environment);
+        for epoch in progress.epoch + 1..=epochs {
+            Self::run_epoch(agent, &mut environment, progress, epoch, &mut |_, _, _| {});
+            metrics.add_tracked(&environment, progress.epoch_step, agent.exploration());
+        }
+        metrics.duration += sta.elapsed();
+    }
 
-impl Default for DisplayConfig {
-    fn default() -> Self {
-        Self {
-            fps: 10,
-            step_time: Duration::from_millis(0),
-            step_time_start: 0,
+    /// Runs one epoch, calling `on_step` after reset (with `epoch_step` 0) and after every step
+    /// with whether the epoch is done
+    fn run_epoch<S, A, E: Environment<S, A>>(
+        agent: &mut impl Agent<S, A>,
+        environment: &mut E,
+        progress: &mut Progress,
+        epoch: usize,
+        on_step: &mut impl FnMut(&mut E, Progress, bool),
+    ) {
+        environment.reset(epoch);
+        progress.epoch = epoch;
+        progress.epoch_step = 0;
+        on_step(environment, *progress, false);
+        let mut done = false;
+        while !done {
+            progress.epoch_step += 1;
+            progress.cumulative_steps += 1;
+            done = agent.step(*progress, environment);
+            on_step(environment, *progress, done);
         }
     }
 }
@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use crate::{
+    agent::{Agent, Checkpoint},
+    environment::Environment,
+    persist::{self, Format, PersistError},
+    progress::Progress,
+};
+
+use super::Runner;
+
+impl Runner {
+    /// Same as `run`, but saves a checkpoint every `config.every` epochs and at the end
+    pub fn run_with_checkpoints<S, A, AG: Agent<S, A> + Checkpoint>(
+        agent: AG,
+        environment: impl Environment<S, A>,
+        epochs: usize,
+        config: &CheckpointConfig,
+    ) -> Result<AG, PersistError> {
+        let progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        Self::run_from(agent, environment, epochs, config, progress)
+    }
+
+    /// Continues from the checkpoint at `config.path`, or from the start if there is none.
+    /// `agent` and `environment` must be created the same way as for the original run.
+    /// The result is the same as an uninterrupted run, as long as the environment
+    /// does not keep state between epochs which affects learning
+    pub fn resume<S, A, AG: Agent<S, A> + Checkpoint>(
+        mut agent: AG,
+        environment: impl Environment<S, A>,
+        epochs: usize,
+        config: &CheckpointConfig,
+    ) -> Result<AG, PersistError> {
+        if !config.path.exists() {
+            return Self::run_with_checkpoints(agent, environment, epochs, config);
+        }
+        let checkpoint: RunnerCheckpoint =
+            persist::load(&config.path, Format::Binary, CHECKPOINT_KIND)?;
+        agent.load_checkpoint(&mut checkpoint.agent.as_slice())?;
+        fastrand::seed(checkpoint.rng);
+        Self::run_from(agent, environment, epochs, config, checkpoint.progress)
+    }
+
+    fn run_from<S, A, AG: Agent<S, A> + Checkpoint>(
+        mut agent: AG,
+        mut environment: impl Environment<S, A>,
+        epochs: usize,
+        config: &CheckpointConfig,
+        mut progress: Progress,
+    ) -> Result<AG, PersistError> {
+        for epoch in progress.epoch + 1..=epochs {
+            Self::run_epoch(
+                &mut agent,
+                &mut environment,
+                &mut progress,
+                epoch,
+                &mut |_, _, _| {},
+            );
+            if epoch.checked_rem(config.every) == Some(0) || epoch == epochs {
+                Self::save_checkpoint(&agent, progress, config)?;
+            }
+        }
+        Ok(agent)
+    }
+
+    fn save_checkpoint(
+        agent: &impl Checkpoint,
+        progress: Progress,
+        config: &CheckpointConfig,
+    ) -> Result<(), PersistError> {
+        let mut bytes = vec![];
+        agent.save_checkpoint(&mut bytes)?;
+        let checkpoint = RunnerCheckpoint {
+            progress,
+            rng: fastrand::get_seed(),
+            agent: bytes,
+        };
+        //write to temp file first, so we never leave a half written checkpoint
+        let temp = config.path.with_extension("tmp");
+        persist::save(&temp, Format::Binary, CHECKPOINT_KIND, &checkpoint)?;
+        std::fs::rename(temp, &config.path)?;
+        Ok(())
+    }
+}
+
+pub struct CheckpointConfig {
+    /// file to save to, and resume from
+    pub path: PathBuf,
+    /// save every this many epochs
+    pub every: usize,
+}
+
+const CHECKPOINT_KIND: &str = "checkpoint";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RunnerCheckpoint {
+    progress: Progress,
+    /// state of the random number generator, so we continue with the same random numbers
+    rng: u64,
+    agent: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::qlearning::QLearning, envs::taxi::TaxiEnvironment,
+        strategy::decliningrandom::DecliningRandom,
+    };
+
+    #[test]
+    fn resume_matches_uninterrupted_run() {
+        let epochs = 300;
+        let agent = || QLearning::new(0.1, 0.9, DecliningRandom::new(epochs, 0.01));
+        let dir = std::env::temp_dir().join(format!("learnwell-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = |name: &str| CheckpointConfig {
+            path: dir.join(name),
+            every: 50,
+        };
+
+        fastrand::seed(7);
+        let whole = config("whole");
+        let whole =
+            Runner::run_with_checkpoints(agent(), TaxiEnvironment::default(), epochs, &whole)
+                .unwrap();
+
+        //stopped after 120 epochs, then resumed with different random numbers as a new process would have
+        fastrand::seed(7);
+        let interrupted = config("interrupted");
+        Runner::run_with_checkpoints(agent(), TaxiEnvironment::default(), 120, &interrupted)
+            .unwrap();
+        fastrand::seed(99);
+        let resumed =
+            Runner::resume(agent(), TaxiEnvironment::default(), epochs, &interrupted).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!whole.qtable.is_empty());
+        assert!(whole.qtable == resumed.qtable);
+    }
+}
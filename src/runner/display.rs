@@ -0,0 +1,320 @@
+use std::{
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
+
+use show_image::{
+    create_window,
+    event::{VirtualKeyCode, WindowEvent},
+    run_context,
+    termination::Termination,
+};
+
+use crate::{
+    agent::Agent,
+    environment::{
+        draw::{text_width, Canvas, GLYPH_HEIGHT},
+        Environment, Frame,
+    },
+    progress::Progress,
+};
+
+use super::{
+    metrics::{Metrics, Tracked},
+    Runner,
+};
+
+impl Runner {
+    /// Starts the window event loop, then runs `main` on another thread.
+    /// Call `run_with_display` from inside `main`.
+    ///
+    /// Never returns: show_image's event loop has to own the main thread, and it ends the process as soon as
+    /// `main` returns, using what `main` returns as the exit code e.g. `Err` exits with 1.
+    /// The trained agent and its metrics come back to `main`, so use or save them there
+    /// ```ignore
+    /// Runner::with_display(move || {
+    ///     let (agent, metrics) = Runner::run_with_display(agent, env, epochs, config);
+    ///     //use the trained agent here, not after with_display
+    ///     agent.save("agent.json", Format::Json)
+    /// });
+    /// ```
+    pub fn with_display<R: Termination>(main: impl FnOnce() -> R + Send + 'static) -> ! {
+        run_context(main)
+    }
+
+    /// Same as `run`, but shows `Environment::get_image` in a window.
+    /// Returns the agent and metrics to the `main` passed to `with_display` as soon as training ends,
+    /// leaving the last frame shown, or once the window is closed if `DisplayConfig::wait_for_close` is set.
+    /// Closing the window early stops training at the end of the current epoch.
+    ///
+    /// Keys:
+    /// - `space` pause or resume
+    /// - `right` or `n` run a single step and pause
+    /// - `up` or `+` speed up by halving `step_time`
+    /// - `down` or `-` slow down by doubling `step_time`
+    ///
+    /// Must be called from inside `Runner::with_display`, otherwise creating the window panics
+    pub fn run_with_display<S, A, AG: Agent<S, A>>(
+        mut agent: AG,
+        environment: impl Environment<S, A>,
+        epochs: usize,
+        config: DisplayConfig,
+    ) -> (AG, Metrics) {
+        let spf = 1.0 / config.fps as f32;
+        let sta = Instant::now();
+        let mut last_render: Option<Instant> = None;
+        let window = create_window("Learnwell", Default::default()).unwrap();
+        let events = window.event_channel().unwrap();
+        let plot_window = config
+            .plot
+            .then(|| create_window("Learnwell metrics", Default::default()).unwrap());
+        println!("space: pause/resume, right: single step, up/down: faster/slower");
+
+        let mut controls = Controls {
+            paused: false,
+            step_once: false,
+            closed: false,
+            step_time: config.step_time,
+            step_time_start: config.step_time_start,
+        };
+        let mut environment = Tracked::new(environment);
+        let mut metrics = Metrics::default();
+        let mut progress: Progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        for epoch in 1..=epochs {
+            Self::run_epoch(
+                &mut agent,
+                &mut environment,
+                &mut progress,
+                epoch,
+                &mut |env, progress, _| {
+                    if controls.closed {
+                        return;
+                    }
+                    controls.poll(&events);
+                    let slow = controls.slow(progress.epoch);
+                    if controls.paused
+                        || slow
+                        || !last_render.is_some_and(|x| x.elapsed().as_secs_f32() <= spf)
+                    {
+                        last_render = Some(Instant::now());
+                        let status = format!(
+                            "epoch {} step {} reward {:.2}{}",
+                            progress.epoch,
+                            progress.epoch_step,
+                            env.last_reward,
+                            if controls.paused { " paused" } else { "" }
+                        );
+                        let frame = compose(&env.get_image(), &config, &status);
+                        let image: show_image::ImageView = (&frame).into();
+                        let _ = window.set_image("im1", image);
+                        if let Some(plot_window) = &plot_window {
+                            let plot = metrics.plot(PLOT_WIDTH, PLOT_HEIGHT, config.plot_average);
+                            let image: show_image::ImageView = (&plot).into();
+                            let _ = plot_window.set_image("plot", image);
+                        }
+                    }
+                    controls.wait(&events);
+                    if slow && !controls.paused {
+                        std::thread::sleep(controls.step_time)
+                    }
+                },
+            );
+            metrics.add_tracked(&environment, progress.epoch_step, agent.exploration());
+            if controls.closed {
+                break;
+            }
+        }
+        metrics.duration = sta.elapsed();
+        if let Some(plot_window) = &plot_window {
+            let plot = metrics.plot(PLOT_WIDTH, PLOT_HEIGHT, config.plot_average);
+            let image: show_image::ImageView = (&plot).into();
+            let _ = plot_window.set_image("plot", image);
+        }
+        println!("Done in {:.2}s", metrics.duration.as_secs_f64());
+        if config.wait_for_close {
+            while events.recv().is_ok() {}
+        }
+        (agent, metrics)
+    }
+}
+
+/// Scales `frame`, and adds grid lines and a status bar if set in `config`
+fn compose(frame: &Frame, config: &DisplayConfig, status: &str) -> Frame<'static> {
+    let scale = config.scale.max(1);
+    let scaled = frame.scale(scale);
+    if !config.grid && !config.status_bar {
+        return scaled;
+    }
+    let margin = 2;
+    let (width, bar_height) = if config.status_bar {
+        (
+            scaled
+                .width()
+                .max(text_width(status, STATUS_SCALE) + margin * 2),
+            GLYPH_HEIGHT * STATUS_SCALE + margin * 2,
+        )
+    } else {
+        (scaled.width(), 0)
+    };
+    let palette = &config.palette;
+    let mut canvas = Canvas::new(width, scaled.height() + bar_height, palette.background);
+    canvas.draw_frame(&scaled, 0, 0);
+    if config.grid && scale > 1 {
+        let (right, bottom) = (scaled.width() as i64 - 1, scaled.height() as i64 - 1);
+        for x in 1..frame.width() {
+            let x = (x * scale) as i64;
+            canvas.line((x, 0), (x, bottom), palette.grid);
+        }
+        for y in 1..frame.height() {
+            let y = (y * scale) as i64;
+            canvas.line((0, y), (right, y), palette.grid);
+        }
+    }
+    if config.status_bar {
+        canvas.text(
+            margin,
+            scaled.height() + margin,
+            status,
+            STATUS_SCALE,
+            palette.text,
+        );
+    }
+    canvas.into_frame()
+}
+
+const STATUS_SCALE: usize = 2;
+const PLOT_WIDTH: usize = 600;
+const PLOT_HEIGHT: usize = 450;
+
+/// Live state changed with the keyboard
+struct Controls {
+    paused: bool,
+    /// run one step, then pause again
+    step_once: bool,
+    closed: bool,
+    step_time: Duration,
+    step_time_start: usize,
+}
+
+impl Controls {
+    fn slow(&self, epoch: usize) -> bool {
+        epoch > self.step_time_start && !self.step_time.is_zero()
+    }
+
+    /// handle all waiting events without blocking
+    fn poll(&mut self, events: &Receiver<WindowEvent>) {
+        loop {
+            match events.try_recv() {
+                Ok(event) => self.handle(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// block while paused, until resumed, stepped or closed
+    fn wait(&mut self, events: &Receiver<WindowEvent>) {
+        while self.paused && !self.step_once && !self.closed {
+            match events.recv() {
+                Ok(event) => self.handle(event),
+                Err(_) => self.closed = true,
+            }
+        }
+        self.step_once = false;
+    }
+
+    fn handle(&mut self, event: WindowEvent) {
+        let WindowEvent::KeyboardInput(event) = event else {
+            return;
+        };
+        if !event.input.state.is_pressed() {
+            return;
+        }
+        match event.input.key_code {
+            Some(VirtualKeyCode::Space) => self.paused = !self.paused,
+            Some(VirtualKeyCode::Right | VirtualKeyCode::N) => {
+                self.paused = true;
+                self.step_once = true;
+            }
+            Some(VirtualKeyCode::Up | VirtualKeyCode::Plus | VirtualKeyCode::NumpadAdd) => {
+                self.step_time /= 2;
+                if self.step_time < Duration::from_millis(1) {
+                    self.step_time = Duration::ZERO;
+                }
+                //changing speed applies straight away
+                self.step_time_start = 0;
+            }
+            Some(VirtualKeyCode::Down | VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract) => {
+                self.step_time = (self.step_time * 2).max(Duration::from_millis(10));
+                self.step_time_start = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct DisplayConfig {
+    /// how many fps to show
+    pub fps: usize,
+    /// slow down each step to take at least this duration.
+    /// Use 0 for no slowdown
+    /// Useful to show learning. Can be changed while running with the up and down keys
+    pub step_time: Duration,
+    /// which epoch to slow down from
+    pub step_time_start: usize,
+    /// show a second window with charts of average return, average length and exploration rate
+    pub plot: bool,
+    /// how many epochs to average over in the charts
+    pub plot_average: usize,
+    /// show each pixel of the environment image as a square of this size, so small images are not blurred
+    pub scale: usize,
+    /// draw lines between the environment's pixels, when `scale` is more than 1
+    pub grid: bool,
+    /// show epoch, step and last reward under the image
+    pub status_bar: bool,
+    pub palette: Palette,
+    /// after training, keep showing the last frame and only return once the window is closed
+    pub wait_for_close: bool,
+}
+
+/// Colours used for the grid and status bar
+pub struct Palette {
+    pub grid: [u8; 3],
+    /// status bar background
+    pub background: [u8; 3],
+    pub text: [u8; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            grid: [60, 60, 60],
+            background: [20, 20, 20],
+            text: [220, 220, 220],
+        }
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            fps: 10,
+            step_time: Duration::from_millis(0),
+            step_time_start: 0,
+            plot: false,
+            plot_average: 100,
+            scale: 1,
+            grid: false,
+            status_bar: false,
+            palette: Palette::default(),
+            wait_for_close: false,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Instant;
+
+use crate::{agent::ParallelAgent, environment::Environment};
+
+use super::{metrics::Metrics, Runner};
+
+impl Runner {
+    /// Runs `epochs` with the best action from `agent`, without exploring or learning.
+    /// Actions the agent does not know yet are picked at random
+    pub fn evaluate<S, A: Clone, AG: ParallelAgent<S, A>>(
+        agent: &AG,
+        mut environment: impl Environment<S, A>,
+        epochs: usize,
+    ) -> Metrics {
+        let sta = Instant::now();
+        let actor = agent.actor();
+        let mut metrics = Metrics::default();
+        for epoch in 1..=epochs {
+            environment.reset(epoch);
+            let mut episode_return = 0.;
+            let mut step = 0;
+            let mut done = false;
+            while !done {
+                let actions = environment.all_actions();
+                if actions.is_empty() {
+                    break;
+                }
+                step += 1;
+                let observation = AG::observe(&mut environment);
+                let action = AG::best_action(&actor, &observation, &actions)
+                    .unwrap_or_else(|| actions[fastrand::usize(0..actions.len())].clone());
+                episode_return += environment.take_action_get_reward(&action);
+                done = environment.should_stop(step);
+            }
+            metrics.add_episode(episode_return, step, None);
+        }
+        metrics.duration = sta.elapsed();
+        metrics
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use crate::environment::{Environment, Frame};
+
+/// Statistics collected while running
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    /// sum of rewards for each completed epoch
+    pub episode_returns: Vec<f64>,
+    /// steps taken in each completed epoch
+    pub episode_lengths: Vec<usize>,
+    /// `Agent::exploration` at the end of each completed epoch
+    pub exploration_rates: Vec<Option<f64>>,
+    /// total steps over all epochs
+    pub steps: usize,
+    /// time spent running
+    pub duration: Duration,
+    /// sum of rewards for each completed epoch before shaping by e.g. `PotentialShaping`, from
+    /// `Environment::unshaped_reward`. Empty if rewards are not shaped, or with the parallel, vec and multi agent runners
+    pub raw_returns: Vec<f64>,
+    /// `Environment::regret` at the end of each completed epoch, which is the total so far rather than for that epoch.
+    /// Empty if the environment does not know its regret, or with the parallel, vec and multi agent runners
+    pub regrets: Vec<f64>,
+}
+
+impl Metrics {
+    /// number of completed epochs
+    pub fn epochs(&self) -> usize {
+        self.episode_returns.len()
+    }
+
+    /// average return of the last `n` epochs, or 0 if there are none
+    pub fn mean_return(&self, n: usize) -> f64 {
+        let last = &self.episode_returns[self.episode_returns.len().saturating_sub(n)..];
+        if last.is_empty() {
+            return 0.;
+        }
+        last.iter().sum::<f64>() / last.len() as f64
+    }
+
+    /// average of the last `n` of `raw_returns`, or 0 if there are none
+    pub fn mean_raw_return(&self, n: usize) -> f64 {
+        let last = &self.raw_returns[self.raw_returns.len().saturating_sub(n)..];
+        if last.is_empty() {
+            return 0.;
+        }
+        last.iter().sum::<f64>() / last.len() as f64
+    }
+
+    pub(crate) fn add_episode(
+        &mut self,
+        episode_return: f64,
+        length: usize,
+        exploration: Option<f64>,
+    ) {
+        self.episode_returns.push(episode_return);
+        self.episode_lengths.push(length);
+        self.exploration_rates.push(exploration);
+        self.steps += length;
+    }
+
+    /// Adds the epoch just run in `environment`
+    pub(crate) fn add_tracked<E>(
+        &mut self,
+        environment: &Tracked<E>,
+        length: usize,
+        exploration: Option<f64>,
+    ) {
+        self.add_episode(environment.episode_return, length, exploration);
+        //kept the same length as episode_returns. Epochs without steps have no unshaped reward and a raw return of 0
+        if environment.raw_return.is_some() || !self.raw_returns.is_empty() {
+            self.raw_returns.resize(self.episode_returns.len() - 1, 0.);
+            self.raw_returns
+                .push(environment.raw_return.unwrap_or_default());
+        }
+        if let Some(regret) = environment.regret {
+            self.regrets.push(regret);
+        }
+    }
+}
+
+/// Passes everything through to the environment, and sums rewards for the current epoch
+pub(crate) struct Tracked<E> {
+    pub environment: E,
+    pub episode_return: f64,
+    pub last_reward: f64,
+    /// sum of `Environment::unshaped_reward` this epoch, if rewards are shaped
+    pub raw_return: Option<f64>,
+    /// `Environment::regret` after the last step
+    pub regret: Option<f64>,
+}
+
+impl<E> Tracked<E> {
+    pub fn new(environment: E) -> Self {
+        Self {
+            environment,
+            episode_return: 0.,
+            last_reward: 0.,
+            raw_return: None,
+            regret: None,
+        }
+    }
+}
+
+impl<S, A, E: Environment<S, A>> Environment<S, A> for Tracked<E> {
+    fn state(&self) -> S {
+        self.environment.state()
+    }
+
+    fn reset(&mut self, epoch: usize) {
+        self.episode_return = 0.;
+        self.last_reward = 0.;
+        self.raw_return = None;
+        self.environment.reset(epoch)
+    }
+
+    fn all_actions(&self) -> Vec<A> {
+        self.environment.all_actions()
+    }
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let reward = self.environment.take_action_get_reward(action);
+        self.episode_return += reward;
+        self.last_reward = reward;
+        if let Some(raw) = self.environment.unshaped_reward() {
+            *self.raw_return.get_or_insert(0.) += raw;
+        }
+        self.regret = self.environment.regret();
+        reward
+    }
+
+    fn should_stop(&mut self, step: usize) -> bool {
+        self.environment.should_stop(step)
+    }
+
+    fn terminated(&self) -> bool {
+        self.environment.terminated()
+    }
+
+    fn unshaped_reward(&self) -> Option<f64> {
+        self.environment.unshaped_reward()
+    }
+
+    fn regret(&self) -> Option<f64> {
+        self.environment.regret()
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.environment.get_image()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.environment.observation()
+    }
+
+    fn render_text(&self) -> String {
+        self.environment.render_text()
+    }
+}
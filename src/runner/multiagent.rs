@@ -0,0 +1,275 @@
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    agent::{JointAgent, Transition},
+    environment::multiagent::{AgentView, MultiAgentEnvironment},
+    progress::Progress,
+};
+
+use super::{metrics::Metrics, Runner};
+
+impl Runner {
+    /// Runs `epochs` with one of `agents` for each agent in `environment`, acting together every step.
+    /// Each learns only from its own steps and rewards.
+    /// Returns the agents, and `Metrics` for each. Panics if there is not an agent for each
+    pub fn run_independent<S, A, E, AG>(
+        mut agents: Vec<AG>,
+        mut environment: E,
+        epochs: usize,
+    ) -> (Vec<AG>, Vec<Metrics>)
+    where
+        A: Clone + PartialEq,
+        E: MultiAgentEnvironment<S, A>,
+        AG: JointAgent<S, A>,
+    {
+        assert_eq!(
+            agents.len(),
+            environment.agents(),
+            "need an agent for each agent in the environment"
+        );
+        let metrics = run_joint(&mut agents, &mut environment, epochs);
+        (agents, metrics)
+    }
+
+    /// Runs `epochs` with `agent` acting for every agent in `environment`, and learning from all of their steps.
+    /// This learns faster when agents are interchangeable, e.g. several taxis.
+    /// If they should act differently, the state needs to say which agent it is.
+    /// Returns the agent, and `Metrics` for each agent in the environment
+    pub fn run_shared<S, A, E, AG>(
+        agent: AG,
+        mut environment: E,
+        epochs: usize,
+    ) -> (AG, Vec<Metrics>)
+    where
+        A: Clone + PartialEq,
+        E: MultiAgentEnvironment<S, A>,
+        AG: JointAgent<S, A>,
+    {
+        let mut agents = [agent];
+        let metrics = run_joint(&mut agents, &mut environment, epochs);
+        let [agent] = agents;
+        (agent, metrics)
+    }
+}
+
+/// Steps every agent in `environment` together. If there is only 1 of `agents`, it acts for all of them
+fn run_joint<S, A, E, AG>(agents: &mut [AG], environment: &mut E, epochs: usize) -> Vec<Metrics>
+where
+    A: Clone + PartialEq,
+    E: MultiAgentEnvironment<S, A>,
+    AG: JointAgent<S, A>,
+{
+    let sta = Instant::now();
+    let count = environment.agents();
+    let shared = agents.len() == 1;
+    let slot = |agent: usize| if shared { 0 } else { agent };
+    let mut metrics = vec![Metrics::default(); count];
+    //steps learned by each of `agents`, for their training schedules
+    let mut learned = vec![0; agents.len()];
+    let mut cumulative_steps = 0;
+    for epoch in 1..=epochs {
+        environment.reset(epoch);
+        let mut returns = vec![0.; count];
+        let mut lengths = vec![0; count];
+        let mut step = 0;
+        let mut done = false;
+        while !done {
+            let actions = environment.all_actions();
+            if actions.is_empty() {
+                break;
+            }
+            step += 1;
+            cumulative_steps += 1;
+            let progress = Progress {
+                epoch,
+                epoch_step: step,
+                cumulative_steps,
+            };
+
+            let active: Vec<usize> = (0..count).filter(|&i| !environment.is_done(i)).collect();
+            let mut states = Vec::with_capacity(active.len());
+            let mut joint = HashMap::new();
+            for &i in &active {
+                let agent = &mut agents[slot(i)];
+                let state = AG::observe(&mut AgentView::new(environment, i));
+                let best = agent.best(&state, &actions);
+                joint.insert(i, agent.pick_action(&actions, best, progress));
+                states.push(state);
+            }
+
+            let rewards = environment.take_actions_get_rewards(&joint);
+            done = environment.should_stop(step);
+
+            for (i, state) in active.into_iter().zip(states) {
+                let action = joint.remove(&i).expect("every active agent has an action");
+                let action_index = actions.iter().position(|x| x == &action).unwrap();
+                let next_state = AG::observe(&mut AgentView::new(environment, i));
+                let transition = Transition {
+                    state,
+                    action,
+                    action_index,
+                    reward: rewards[i],
+                    next_state,
+                    done: done || environment.is_done(i),
+                    terminated: environment.is_done(i),
+                };
+                learned[slot(i)] += 1;
+                let progress = Progress {
+                    cumulative_steps: learned[slot(i)],
+                    ..progress
+                };
+                agents[slot(i)].learn(transition, progress);
+                returns[i] += rewards[i];
+                lengths[i] += 1;
+            }
+        }
+        for (i, metrics) in metrics.iter_mut().enumerate() {
+            metrics.add_episode(returns[i], lengths[i], agents[slot(i)].exploration());
+        }
+    }
+    for metrics in &mut metrics {
+        metrics.duration = sta.elapsed();
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::{Agent, ParallelAgent},
+        environment::Environment,
+    };
+
+    /// 2 agents, which each get 10 times their index plus their action as a reward.
+    /// Agent 1 is done after picking 1 three times, and epochs are 4 steps
+    struct Race {
+        moves: Vec<usize>,
+    }
+
+    impl MultiAgentEnvironment<usize, usize> for Race {
+        fn agents(&self) -> usize {
+            2
+        }
+        fn state(&self, agent: usize) -> usize {
+            self.moves[agent]
+        }
+        fn reset(&mut self, _epoch: usize) {
+            self.moves = vec![0; 2];
+        }
+        fn all_actions(&self) -> Vec<usize> {
+            vec![0, 1]
+        }
+        fn take_actions_get_rewards(&mut self, actions: &HashMap<usize, usize>) -> Vec<f64> {
+            let mut rewards = vec![0.; 2];
+            for (&agent, &action) in actions {
+                self.moves[agent] += action;
+                rewards[agent] = (agent * 10 + action) as f64;
+            }
+            rewards
+        }
+        fn is_done(&self, agent: usize) -> bool {
+            agent == 1 && self.moves[agent] >= 3
+        }
+        fn should_stop(&mut self, step: usize) -> bool {
+            step >= 4
+        }
+    }
+
+    /// Always picks `action`, keeping every transition it learns from as (state, reward, done, terminated)
+    struct Fixed {
+        action: usize,
+        learned: Vec<(usize, f64, bool, bool)>,
+    }
+
+    fn fixed(action: usize) -> Fixed {
+        Fixed {
+            action,
+            learned: vec![],
+        }
+    }
+
+    impl Agent<usize, usize> for Fixed {
+        fn step(&mut self, _progress: Progress, _env: &mut dyn Environment<usize, usize>) -> bool {
+            true
+        }
+        fn pick_action(&mut self, _actions: &[usize], _best: Option<usize>, _: Progress) -> usize {
+            self.action
+        }
+    }
+
+    impl ParallelAgent<usize, usize> for Fixed {
+        type Observation = usize;
+        type Actor = ();
+
+        fn observe(env: &mut dyn Environment<usize, usize>) -> usize {
+            env.state()
+        }
+        fn actor(&self) {}
+        fn best_action(_actor: &(), _observation: &usize, _actions: &[usize]) -> Option<usize> {
+            None
+        }
+        fn learn(&mut self, transition: Transition<usize, usize>, _progress: Progress) {
+            self.learned.push((
+                transition.state,
+                transition.reward,
+                transition.done,
+                transition.terminated,
+            ));
+        }
+    }
+
+    impl JointAgent<usize, usize> for Fixed {
+        fn best(&self, _observation: &usize, _actions: &[usize]) -> Option<usize> {
+            None
+        }
+    }
+
+    #[test]
+    fn independent_agents_get_their_own_rewards() {
+        let env = Race { moves: vec![] };
+        let (agents, metrics) = Runner::run_independent(vec![fixed(0), fixed(1)], env, 2);
+
+        assert_eq!(metrics[0].episode_returns, vec![0., 0.]);
+        assert_eq!(metrics[0].episode_lengths, vec![4, 4]);
+        assert_eq!(metrics[1].episode_returns, vec![33., 33.]);
+        assert_eq!(metrics[1].episode_lengths, vec![3, 3]);
+        assert_eq!(metrics[1].steps, 6);
+
+        //cut short by the step limit
+        assert_eq!(
+            agents[0].learned[2..4],
+            [(0, 0., false, false), (0, 0., true, false)]
+        );
+        assert_eq!(agents[0].learned.len(), 8);
+        //stops acting once done
+        assert_eq!(
+            agents[1].learned[..3],
+            [
+                (0, 11., false, false),
+                (1, 11., false, false),
+                (2, 11., true, true)
+            ]
+        );
+        assert_eq!(agents[1].learned.len(), 6);
+    }
+
+    #[test]
+    fn shared_agent_learns_from_every_agent() {
+        let env = Race { moves: vec![] };
+        let (agent, metrics) = Runner::run_shared(fixed(1), env, 1);
+
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].episode_returns, vec![4.]);
+        assert_eq!(metrics[1].episode_returns, vec![33.]);
+        assert_eq!(agent.learned.len(), 7);
+        let rewards: Vec<f64> = agent.learned.iter().map(|x| x.1).collect();
+        assert_eq!(rewards, vec![1., 11., 1., 11., 1., 11., 1.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "need an agent for each agent")]
+    fn independent_needs_an_agent_each() {
+        Runner::run_independent(vec![fixed(0)], Race { moves: vec![] }, 1);
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    agent::{ParallelAgent, Transition},
+    environment::Environment,
+    progress::Progress,
+    strategy::ExploreStrategy,
+};
+
+use super::{metrics::Metrics, Runner};
+
+impl Runner {
+    /// Runs `epochs` over `config.workers` threads. Each worker steps its own clone of `environment`,
+    /// picking actions with a copy of the agent and its own strategy created with `strategy`.
+    /// The agent learns from every step in this thread, and workers get an updated copy every `config.sync_steps`.
+    /// The agent's own strategy is not used.
+    ///
+    /// Results vary between runs, as they depend on the order steps arrive from the workers
+    pub fn run_parallel<S, A, E, AG, ST>(
+        mut agent: AG,
+        environment: E,
+        epochs: usize,
+        config: ParallelConfig,
+        strategy: impl Fn() -> ST + Sync,
+    ) -> (AG, Metrics)
+    where
+        E: Environment<S, A> + Clone + Send,
+        A: Clone + PartialEq + Send,
+        AG: ParallelAgent<S, A>,
+        ST: ExploreStrategy<A>,
+    {
+        let sta = Instant::now();
+        let actor = Mutex::new(Arc::new(agent.actor()));
+        let next_epoch = AtomicUsize::new(1);
+        let (tx, rx) = mpsc::sync_channel(config.queue_size);
+        let seeds: Vec<u64> = (0..config.workers.max(1))
+            .map(|_| fastrand::u64(..))
+            .collect();
+        let mut metrics = Metrics::default();
+
+        thread::scope(|scope| {
+            for seed in seeds {
+                let tx = tx.clone();
+                let environment = environment.clone();
+                let (actor, next_epoch, strategy) = (&actor, &next_epoch, &strategy);
+                scope.spawn(move || {
+                    fastrand::seed(seed);
+                    worker::<S, A, E, AG, ST>(
+                        environment,
+                        strategy(),
+                        actor,
+                        next_epoch,
+                        epochs,
+                        tx,
+                    )
+                });
+            }
+            //so the loop ends when all workers are done
+            drop(tx);
+
+            let mut progress: Progress = Progress {
+                epoch: 1,
+                epoch_step: 0,
+                cumulative_steps: 0,
+            };
+            for message in rx {
+                match message {
+                    Message::Step(transition) => {
+                        progress.cumulative_steps += 1;
+                        agent.learn(transition, progress);
+                        if progress.cumulative_steps.checked_rem(config.sync_steps) == Some(0) {
+                            *actor.lock().unwrap() = Arc::new(agent.actor());
+                        }
+                    }
+                    Message::Done {
+                        episode_return,
+                        length,
+                        exploration,
+                    } => {
+                        metrics.add_episode(episode_return, length, exploration);
+                        progress.epoch = metrics.epochs() + 1;
+                    }
+                }
+            }
+        });
+
+        metrics.duration = sta.elapsed();
+        (agent, metrics)
+    }
+}
+
+pub struct ParallelConfig {
+    /// number of threads stepping environments. The agent learns in the calling thread
+    pub workers: usize,
+    /// how many steps the agent learns from before workers get an updated copy.
+    /// Copying can be slow for large qtables. Use 0 to never update
+    pub sync_steps: usize,
+    /// how many steps can wait for the agent before workers pause
+    pub queue_size: usize,
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        let cores = thread::available_parallelism().map_or(1, |x| x.get());
+        Self {
+            workers: cores.saturating_sub(1).max(1),
+            sync_steps: 1000,
+            queue_size: 10_000,
+        }
+    }
+}
+
+enum Message<O, A> {
+    Step(Transition<O, A>),
+    /// an epoch ended
+    Done {
+        episode_return: f64,
+        length: usize,
+        exploration: Option<f64>,
+    },
+}
+
+/// Runs epochs until `epochs` have been started over all workers
+fn worker<S, A, E, AG, ST>(
+    mut environment: E,
+    mut strategy: ST,
+    actor: &Mutex<Arc<AG::Actor>>,
+    next_epoch: &AtomicUsize,
+    epochs: usize,
+    tx: SyncSender<Message<AG::Observation, A>>,
+) where
+    E: Environment<S, A>,
+    A: Clone + PartialEq,
+    AG: ParallelAgent<S, A>,
+    ST: ExploreStrategy<A>,
+{
+    let mut cumulative_steps = 0;
+    loop {
+        let epoch = next_epoch.fetch_add(1, Ordering::Relaxed);
+        if epoch > epochs {
+            return;
+        }
+        //keep the same copy for the whole epoch
+        let current = actor.lock().unwrap().clone();
+        environment.reset(epoch);
+        let mut progress = Progress {
+            epoch,
+            epoch_step: 0,
+            cumulative_steps,
+        };
+        let mut episode_return = 0.;
+        let mut done = false;
+        while !done {
+            let actions = environment.all_actions();
+            if actions.is_empty() {
+                break;
+            }
+            progress.epoch_step += 1;
+            progress.cumulative_steps += 1;
+            let state = AG::observe(&mut environment);
+            let best = AG::best_action(&current, &state, &actions);
+            let action = strategy.pick_action(&actions, best, progress);
+            let action_index = actions.iter().position(|x| x == &action).unwrap();
+            let reward = environment.take_action_get_reward(&action);
+            done = environment.should_stop(progress.epoch_step);
+            episode_return += reward;
+            let next_state = AG::observe(&mut environment);
+            let transition = Transition {
+                state,
+                action,
+                action_index,
+                reward,
+                next_state,
+                done,
+                terminated: environment.terminated(),
+            };
+            if tx.send(Message::Step(transition)).is_err() {
+                return;
+            }
+        }
+        cumulative_steps = progress.cumulative_steps;
+        let _ = tx.send(Message::Done {
+            episode_return,
+            length: progress.epoch_step,
+            exploration: strategy.exploration(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::{qlearning::QLearning, Policy},
+        strategy::decliningrandom::DecliningRandom,
+    };
+
+    /// Pick 1 for a reward of 1 or 0 for nothing, 5 times an epoch
+    #[derive(Clone)]
+    struct Pick;
+
+    impl Environment<u8, u8> for Pick {
+        fn state(&self) -> u8 {
+            0
+        }
+        fn reset(&mut self, _epoch: usize) {}
+        fn all_actions(&self) -> Vec<u8> {
+            vec![0, 1]
+        }
+        fn take_action_get_reward(&mut self, action: &u8) -> f64 {
+            *action as f64
+        }
+        fn should_stop(&mut self, step: usize) -> bool {
+            step >= 5
+        }
+    }
+
+    #[test]
+    fn counts_every_epoch_and_step_from_all_workers() {
+        fastrand::seed(7);
+        let epochs = 200;
+        let config = ParallelConfig {
+            workers: 3,
+            sync_steps: 10,
+            queue_size: 16,
+        };
+        let agent = QLearning::new(0.2, 0.5, DecliningRandom::new(1, 0.));
+        let (agent, metrics) = Runner::run_parallel(agent, Pick, epochs, config, || {
+            DecliningRandom::new(100, 0.)
+        });
+
+        assert_eq!(metrics.epochs(), epochs);
+        assert_eq!(metrics.steps, epochs * 5);
+        assert!(metrics.episode_lengths.iter().all(|x| *x == 5));
+        //learned in this thread from the workers' steps
+        assert_eq!(agent.act(&0, &[0, 1]), 1);
+        assert!(agent.action_values(&0, &[1])[0] > 1.5);
+        //workers used the updated copy once exploration stopped
+        assert_eq!(metrics.episode_returns.last(), Some(&5.));
+    }
+}
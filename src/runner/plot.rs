@@ -0,0 +1,194 @@
+use crate::environment::{
+    draw::{Canvas, Color, GLYPH_HEIGHT},
+    Frame,
+};
+
+use super::metrics::Metrics;
+
+const BACKGROUND: Color = [20, 20, 20];
+const AXIS: Color = [70, 70, 70];
+const TEXT: Color = [200, 200, 200];
+const TEXT_SCALE: usize = 2;
+
+impl Metrics {
+    /// Draws 3 charts: average return and average length of the last `average` epochs,
+    /// and the exploration rate, over all epochs so far.
+    /// Used by `run_with_display`, or save with `Frame::save_png` after training
+    pub fn plot(&self, width: usize, height: usize, average: usize) -> Frame<'static> {
+        let mut canvas = Canvas::new(width, height, BACKGROUND);
+        let lengths: Vec<f64> = self.episode_lengths.iter().map(|x| *x as f64).collect();
+        let exploration: Vec<f64> = self
+            .exploration_rates
+            .iter()
+            .map(|x| x.unwrap_or(f64::NAN))
+            .collect();
+        let panel = height / 3;
+        let charts = [
+            (
+                "return",
+                rolling_mean(&self.episode_returns, average),
+                [80, 200, 80],
+            ),
+            ("length", rolling_mean(&lengths, average), [80, 140, 230]),
+            ("explore", exploration, [230, 150, 50]),
+        ];
+        for (i, (name, values, color)) in charts.into_iter().enumerate() {
+            chart(&mut canvas, i * panel, panel, name, &values, color);
+        }
+        canvas.into_frame()
+    }
+}
+
+/// Average of each value and up to `average - 1` values before it
+fn rolling_mean(values: &[f64], average: usize) -> Vec<f64> {
+    let average = average.max(1);
+    let mut sum = 0.;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            sum += x;
+            if i >= average {
+                sum -= values[i - average];
+            }
+            sum / (i + 1).min(average) as f64
+        })
+        .collect()
+}
+
+/// Smallest and largest value, ignoring NaN. `None` if there are no values to draw
+fn value_range(values: &[f64]) -> Option<(f64, f64)> {
+    let valid = values.iter().filter(|x| !x.is_nan());
+    let min = valid.clone().fold(f64::INFINITY, |a, b| a.min(*b));
+    let max = valid.fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+    (min <= max).then_some((min, max))
+}
+
+/// Maps values from `min` to `max` onto pixel rows from `bottom` up to `top`
+struct Axis {
+    min: f64,
+    range: f64,
+    top: usize,
+    bottom: usize,
+}
+
+impl Axis {
+    fn new((min, max): (f64, f64), top: usize, bottom: usize) -> Self {
+        Self {
+            min,
+            //avoid dividing by 0 when all values are the same
+            range: if max > min { max - min } else { 1. },
+            top,
+            bottom,
+        }
+    }
+
+    fn y(&self, value: f64) -> i64 {
+        let fraction = (value - self.min) / self.range;
+        self.bottom as i64 - (fraction * (self.bottom - self.top) as f64) as i64
+    }
+}
+
+/// Line chart of `values` in a panel starting at `top`, with its name, latest value and range.
+/// NaN values are not drawn
+fn chart(canvas: &mut Canvas, top: usize, height: usize, name: &str, values: &[f64], color: Color) {
+    let margin = 4;
+    let last = values.last().filter(|x| !x.is_nan());
+    let range = value_range(values);
+
+    let label = match (last, range) {
+        (Some(last), Some((min, max))) => format!("{name} {last:.2}  min {min:.2} max {max:.2}"),
+        _ => name.to_string(),
+    };
+    canvas.text(margin, top + margin, &label, TEXT_SCALE, TEXT);
+
+    let chart_top = top + margin * 2 + GLYPH_HEIGHT * TEXT_SCALE;
+    let chart_bottom = (top + height).saturating_sub(margin);
+    let chart_width = canvas.width.saturating_sub(margin * 2);
+    let (Some(_), Some((min, max))) = (last, range) else {
+        return;
+    };
+    if chart_bottom <= chart_top || chart_width < 2 {
+        return;
+    }
+    let axis = Axis::new((min, max), chart_top, chart_bottom);
+    if min < 0. && max > 0. {
+        let zero = axis.y(0.);
+        canvas.line(
+            (margin as i64, zero),
+            ((margin + chart_width) as i64, zero),
+            AXIS,
+        );
+    }
+
+    //one point per pixel column, so long runs are sampled
+    let mut previous: Option<(i64, i64)> = None;
+    for x in 0..chart_width {
+        let index = x * values.len() / chart_width;
+        let value = values[index.min(values.len() - 1)];
+        if value.is_nan() {
+            previous = None;
+            continue;
+        }
+        let point = ((margin + x) as i64, axis.y(value));
+        canvas.line(previous.unwrap_or(point), point, color);
+        previous = Some(point);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(frame: &Frame, color: Color) -> usize {
+        frame.data().chunks(3).filter(|x| *x == color).count()
+    }
+
+    #[test]
+    fn rolling_mean_starts_with_fewer_values() {
+        assert_eq!(rolling_mean(&[2., 4., 6., 8.], 2), [2., 3., 5., 7.]);
+        assert_eq!(rolling_mean(&[2., 4.], 0), [2., 4.]);
+        assert!(rolling_mean(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn range_ignores_nan() {
+        assert_eq!(value_range(&[3., f64::NAN, -1., 2.]), Some((-1., 3.)));
+        assert_eq!(value_range(&[5.]), Some((5., 5.)));
+        assert_eq!(value_range(&[f64::NAN]), None);
+        assert_eq!(value_range(&[]), None);
+    }
+
+    #[test]
+    fn axis_maps_range_to_rows() {
+        let axis = Axis::new((-10., 10.), 20, 120);
+        assert_eq!(axis.y(-10.), 120);
+        assert_eq!(axis.y(10.), 20);
+        assert_eq!(axis.y(0.), 70);
+        //a single value is drawn at the bottom
+        assert_eq!(Axis::new((5., 5.), 20, 120).y(5.), 120);
+    }
+
+    #[test]
+    fn empty_metrics_draw_only_labels() {
+        let frame = Metrics::default().plot(120, 150, 10);
+        assert_eq!((frame.width(), frame.height()), (120, 150));
+        assert!(count(&frame, TEXT) > 0);
+        assert_eq!(count(&frame, BACKGROUND) + count(&frame, TEXT), 120 * 150);
+    }
+
+    #[test]
+    fn single_epoch_draws_flat_lines() {
+        let metrics = Metrics {
+            episode_returns: vec![-3.],
+            episode_lengths: vec![7],
+            exploration_rates: vec![None],
+            ..Default::default()
+        };
+        let frame = metrics.plot(120, 150, 10);
+        //a line across the chart for return and length, and nothing for an unknown exploration rate
+        assert_eq!(count(&frame, [80, 200, 80]), 120 - 8);
+        assert_eq!(count(&frame, [80, 140, 230]), 120 - 8);
+        assert_eq!(count(&frame, [230, 150, 50]), 0);
+    }
+}
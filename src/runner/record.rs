@@ -0,0 +1,182 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use crate::{
+    agent::Agent,
+    environment::{Environment, Frame},
+    progress::Progress,
+};
+
+use super::Runner;
+
+impl Runner {
+    /// Same as `run`, but captures frames from `get_image` without a window, and writes them to `config.dir`
+    pub fn run_with_recording<S, A, AG: Agent<S, A>>(
+        mut agent: AG,
+        mut environment: impl Environment<S, A>,
+        epochs: usize,
+        config: &RecordConfig,
+    ) -> std::io::Result<AG> {
+        std::fs::create_dir_all(&config.dir)?;
+        let mut progress: Progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        let mut frames = vec![];
+        for epoch in 1..=epochs {
+            let record = config.epochs.contains(epoch, epochs);
+            Self::run_epoch(
+                &mut agent,
+                &mut environment,
+                &mut progress,
+                epoch,
+                &mut |env, progress, done| {
+                    if record
+                        && (done
+                            || progress.epoch_step.checked_rem(config.every_steps.max(1))
+                                == Some(0))
+                    {
+                        frames.push(env.get_image().scale(config.scale));
+                    }
+                },
+            );
+            if record {
+                config.write(epoch, &frames)?;
+                frames.clear();
+            }
+        }
+        Ok(agent)
+    }
+}
+
+pub struct RecordConfig {
+    /// folder to write to
+    pub dir: PathBuf,
+    /// which epochs to record
+    pub epochs: RecordEpochs,
+    /// capture a frame every this many steps. The first and last frames are always captured
+    pub every_steps: usize,
+    /// nearest neighbour upscale, since environments often have tiny images
+    pub scale: usize,
+    pub format: RecordFormat,
+}
+
+pub enum RecordEpochs {
+    All,
+    Last,
+    Every(usize),
+    List(Vec<usize>),
+}
+
+pub enum RecordFormat {
+    /// one animated gif per epoch, with `delay` between frames in hundredths of a second
+    Gif { delay: u16 },
+    /// numbered png files for each epoch
+    Png,
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("recordings"),
+            epochs: RecordEpochs::Last,
+            every_steps: 1,
+            scale: 10,
+            format: RecordFormat::Gif { delay: 10 },
+        }
+    }
+}
+
+impl RecordEpochs {
+    fn contains(&self, epoch: usize, epochs: usize) -> bool {
+        match self {
+            RecordEpochs::All => true,
+            RecordEpochs::Last => epoch == epochs,
+            RecordEpochs::Every(n) => epoch.checked_rem(*n) == Some(0),
+            RecordEpochs::List(list) => list.contains(&epoch),
+        }
+    }
+}
+
+impl RecordConfig {
+    fn write(&self, epoch: usize, frames: &[Frame]) -> std::io::Result<()> {
+        if frames.is_empty() || frames[0].is_empty() {
+            return Ok(());
+        }
+        match self.format {
+            RecordFormat::Gif { delay } => {
+                let size = gif_size(frames)?;
+                let path = self.dir.join(format!("epoch-{epoch}.gif"));
+                let mut writer = BufWriter::new(File::create(path)?);
+                write_gif(&mut writer, frames, size, delay)?;
+                writer.flush()
+            }
+            RecordFormat::Png => {
+                for (i, frame) in frames.iter().enumerate() {
+                    frame.save_png(self.dir.join(format!("epoch-{epoch}-{i:04}.png")))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Width and height of every frame, which gifs need to be the same and at most 65535
+fn gif_size(frames: &[Frame]) -> std::io::Result<(u16, u16)> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+    let (width, height) = (frames[0].width(), frames[0].height());
+    if let Some((i, frame)) = frames
+        .iter()
+        .enumerate()
+        .find(|(_, x)| (x.width(), x.height()) != (width, height))
+    {
+        return Err(invalid(format!(
+            "frame {i} is {}x{}, the first is {width}x{height}",
+            frame.width(),
+            frame.height()
+        )));
+    }
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(invalid(format!(
+            "{width}x{height} frames are too big for a gif, which is at most 65535x65535"
+        ))),
+    }
+}
+
+fn write_gif(
+    writer: impl Write,
+    frames: &[Frame],
+    (width, height): (u16, u16),
+    delay: u16,
+) -> std::io::Result<()> {
+    let to_io = |e: gif::EncodingError| std::io::Error::other(e.to_string());
+    let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(to_io)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(to_io)?;
+    for frame in frames {
+        let rgb = frame.to_rgb();
+        let mut gif_frame = gif::Frame::from_rgb(width, height, rgb.data());
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame).map_err(to_io)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gif_frames_must_fit_and_match() {
+        let frame = |width, height| Frame::gray(width, height, vec![0; width * height]);
+        assert_eq!(gif_size(&[frame(3, 2), frame(3, 2)]).unwrap(), (3, 2));
+        let error = gif_size(&[frame(3, 2), frame(2, 3)]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(gif_size(&[frame(65_536, 1)]).is_err());
+        assert!(gif_size(&[frame(65_535, 1)]).is_ok());
+    }
+}
@@ -0,0 +1,213 @@
+use std::time::Instant;
+
+use crate::{
+    agent::ParallelAgent,
+    environment::{
+        turnbased::{CurrentPlayer, TurnBasedEnvironment},
+        Environment, Frame,
+    },
+    progress::Progress,
+};
+
+use super::{
+    metrics::{Metrics, Tracked},
+    Runner,
+};
+
+impl Runner {
+    /// Trains `agent` for `epochs` games of `game` against frozen copies of itself.
+    /// A copy is added to a pool of opponents every `config.snapshot_every` epochs, and each game is
+    /// played against one picked at random from the pool. The agent takes turns being each player.
+    ///
+    /// The agent learns as if the opponents' moves were part of the environment, so the next state
+    /// is its next turn. `Metrics` has the agent's return in each game
+    pub fn run_self_play<S, A, G, AG>(
+        mut agent: AG,
+        mut game: G,
+        epochs: usize,
+        config: &SelfPlayConfig,
+    ) -> (AG, Metrics)
+    where
+        A: Clone,
+        G: TurnBasedEnvironment<S, A>,
+        AG: ParallelAgent<S, A>,
+    {
+        let sta = Instant::now();
+        let mut metrics = Metrics::default();
+        let mut progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        let mut pool = vec![agent.actor()];
+        for epoch in 1..=epochs {
+            let opponent = &pool[fastrand::usize(0..pool.len())];
+            let seat = Seat::new(&mut game, config.valid_actions_only, |game: &mut G| {
+                opponent_action::<S, A, G, AG>(game, opponent, config.random_moves)
+            });
+            let mut seat = Tracked::new(seat);
+            Self::run_epoch(
+                &mut agent,
+                &mut seat,
+                &mut progress,
+                epoch,
+                &mut |_, _, _| {},
+            );
+            metrics.add_tracked(&seat, progress.epoch_step, agent.exploration());
+
+            if epoch.checked_rem(config.snapshot_every) == Some(0) {
+                pool.push(agent.actor());
+                if pool.len() > config.pool_size.max(1) {
+                    pool.remove(0);
+                }
+            }
+        }
+        metrics.duration = sta.elapsed();
+        (agent, metrics)
+    }
+
+    /// Plays `epochs` games of `agent`'s best moves against random moves, without learning.
+    /// The agent takes turns being each player, and is offered actions as in `config`.
+    /// `Metrics` has the agent's return in each game
+    pub fn evaluate_game<S, A, G, AG>(
+        agent: &AG,
+        mut game: G,
+        epochs: usize,
+        config: &SelfPlayConfig,
+    ) -> Metrics
+    where
+        A: Clone,
+        G: TurnBasedEnvironment<S, A>,
+        AG: ParallelAgent<S, A>,
+    {
+        let seat = Seat::new(&mut game, config.valid_actions_only, |game: &mut G| {
+            random_action(game)
+        });
+        Self::evaluate(agent, seat, epochs)
+    }
+}
+
+pub struct SelfPlayConfig {
+    /// epochs between adding a copy of the agent to the pool of opponents. 0 to only play the untrained agent
+    pub snapshot_every: usize,
+    /// most copies kept, dropping the oldest. 1 always plays the latest copy
+    pub pool_size: usize,
+    /// chance of opponents making a random move, so the agent sees more positions
+    pub random_moves: f64,
+    /// offer the agent only `TurnBasedEnvironment::valid_actions`, so a qtable does not need to learn which moves are invalid.
+    /// Turn off for agents which need the same actions every step, e.g. `DeepQLearning`
+    pub valid_actions_only: bool,
+}
+
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_every: 1000,
+            pool_size: 10,
+            random_moves: 0.1,
+            valid_actions_only: true,
+        }
+    }
+}
+
+/// Best move of the frozen copy `actor`, or a random move
+fn opponent_action<S, A, G, AG>(game: &mut G, actor: &AG::Actor, random_moves: f64) -> A
+where
+    A: Clone,
+    G: TurnBasedEnvironment<S, A>,
+    AG: ParallelAgent<S, A>,
+{
+    let actions = game.all_actions();
+    let best = (fastrand::f64() >= random_moves)
+        .then(|| AG::best_action(actor, &AG::observe(&mut CurrentPlayer(game)), &actions))
+        .flatten();
+    best.unwrap_or_else(|| random_action(game))
+}
+
+/// Random move from `TurnBasedEnvironment::valid_actions`
+fn random_action<S, A: Clone, G: TurnBasedEnvironment<S, A>>(game: &G) -> A {
+    let actions = game.valid_actions();
+    actions[fastrand::usize(0..actions.len())].clone()
+}
+
+/// The game as an `Environment` for one player, where `opponent` picks the moves of the other players
+struct Seat<'a, G, F> {
+    game: &'a mut G,
+    valid_actions_only: bool,
+    opponent: F,
+    player: usize,
+}
+
+impl<'a, G, F> Seat<'a, G, F> {
+    fn new(game: &'a mut G, valid_actions_only: bool, opponent: F) -> Self {
+        Self {
+            game,
+            valid_actions_only,
+            opponent,
+            player: 0,
+        }
+    }
+
+    /// Plays the other players until it is our turn, and returns our rewards from their moves
+    fn play_opponents<S, A>(&mut self) -> f64
+    where
+        G: TurnBasedEnvironment<S, A>,
+        F: FnMut(&mut G) -> A,
+    {
+        let mut reward = 0.;
+        while !self.game.is_over() && self.game.current_player() != self.player {
+            let action = (self.opponent)(self.game);
+            reward += self.game.take_action_get_rewards(&action)[self.player];
+        }
+        reward
+    }
+}
+
+impl<S, A, G, F> Environment<S, A> for Seat<'_, G, F>
+where
+    G: TurnBasedEnvironment<S, A>,
+    F: FnMut(&mut G) -> A,
+{
+    fn state(&self) -> S {
+        self.game.state()
+    }
+
+    /// Takes turns being each player
+    fn reset(&mut self, epoch: usize) {
+        self.game.reset(epoch);
+        self.player = epoch % self.game.players();
+        self.play_opponents();
+    }
+
+    fn all_actions(&self) -> Vec<A> {
+        match self.valid_actions_only {
+            true => self.game.valid_actions(),
+            false => self.game.all_actions(),
+        }
+    }
+
+    fn take_action_get_reward(&mut self, action: &A) -> f64 {
+        let reward = self.game.take_action_get_rewards(action)[self.player];
+        reward + self.play_opponents()
+    }
+
+    fn should_stop(&mut self, _step: usize) -> bool {
+        self.game.is_over()
+    }
+
+    fn terminated(&self) -> bool {
+        self.game.is_over()
+    }
+
+    fn get_image(&mut self) -> Frame<'_> {
+        self.game.get_image()
+    }
+
+    fn observation(&mut self) -> Vec<f32> {
+        self.game.observation()
+    }
+
+    fn render_text(&self) -> String {
+        self.game.render_text()
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{agent::Agent, environment::Environment, progress::Progress};
+
+use super::{metrics::Tracked, Runner};
+
+impl Runner {
+    /// Same as `run`, but prints `Environment::render_text` to the terminal, redrawing in place.
+    /// Useful when a window cannot be opened e.g. over ssh
+    pub fn run_with_text<S, A, AG: Agent<S, A>>(
+        mut agent: AG,
+        environment: impl Environment<S, A>,
+        epochs: usize,
+        config: TextConfig,
+    ) -> AG {
+        let spf = 1.0 / config.fps as f32;
+        let mut last_render: Option<Instant> = None;
+        let mut out = std::io::stdout();
+        //clear screen
+        print!("\x1b[2J");
+
+        let mut environment = Tracked::new(environment);
+        let mut progress: Progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        for epoch in 1..=epochs {
+            Self::run_epoch(
+                &mut agent,
+                &mut environment,
+                &mut progress,
+                epoch,
+                &mut |env, progress, _| {
+                    let slow =
+                        progress.epoch > config.step_time_start && !config.step_time.is_zero();
+                    if slow || !last_render.is_some_and(|x| x.elapsed().as_secs_f32() <= spf) {
+                        last_render = Some(Instant::now());
+                        //cursor home, draw, then clear anything left over from last time
+                        let _ = write!(
+                            out,
+                            "\x1b[H{}\nepoch {} step {} reward {:.2}\x1b[J",
+                            env.render_text(),
+                            progress.epoch,
+                            progress.epoch_step,
+                            env.last_reward
+                        );
+                        let _ = out.flush();
+                    }
+                    if slow {
+                        std::thread::sleep(config.step_time)
+                    }
+                },
+            );
+        }
+        println!();
+        agent
+    }
+}
+
+pub struct TextConfig {
+    /// how many times per second to redraw
+    pub fps: usize,
+    /// slow down each step to take at least this duration, and draw every step.
+    /// Use 0 for no slowdown
+    pub step_time: Duration,
+    /// which epoch to slow down from
+    pub step_time_start: usize,
+}
+
+impl Default for TextConfig {
+    fn default() -> Self {
+        Self {
+            fps: 10,
+            step_time: Duration::from_millis(0),
+            step_time_start: 0,
+        }
+    }
+}
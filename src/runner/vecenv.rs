@@ -0,0 +1,77 @@
+use std::time::Instant;
+
+use crate::{
+    agent::VecAgent,
+    environment::{vecenv::VecEnv, Environment},
+    progress::Progress,
+};
+
+use super::{metrics::Metrics, Runner};
+
+impl Runner {
+    /// Steps all environments in `envs` together until `epochs` epochs have finished over all of them.
+    /// `Progress::epoch` is the number of finished epochs plus 1, and `epoch_step` counts batched steps.
+    /// Epochs which finish in the same step as the last one counted are left out of the metrics
+    pub fn run_vec<S, A, E: Environment<S, A>, AG: VecAgent<S, A>>(
+        mut agent: AG,
+        mut envs: VecEnv<E>,
+        epochs: usize,
+    ) -> (AG, Metrics) {
+        let sta = Instant::now();
+        let mut metrics = Metrics::default();
+        let mut progress: Progress = Progress {
+            epoch: 0,
+            epoch_step: 0,
+            cumulative_steps: 0,
+        };
+        envs.reset();
+        while metrics.epochs() < epochs {
+            progress.epoch = metrics.epochs() + 1;
+            progress.epoch_step += 1;
+            progress.cumulative_steps += envs.len();
+            let step = agent.step_vec(progress, &mut envs);
+            let remaining = epochs - metrics.epochs();
+            for episode in step.finished.into_iter().take(remaining) {
+                metrics.add_episode(episode.episode_return, episode.length, agent.exploration());
+            }
+        }
+        metrics.duration = sta.elapsed();
+        (agent, metrics)
+    }
+}
+
+#[cfg(all(test, feature = "mlp"))]
+mod tests {
+    use crate::{
+        agent::{
+            deepqlearning::{DeepQLearning, DeepQLearningConfig},
+            nnbackend::mlp::Mlp,
+        },
+        environment::{vecenv::VecEnv, wrappers::TimeLimit},
+        envs::cartpole::CartPole,
+        runner::Runner,
+        strategy::decliningrandom::DecliningRandom,
+    };
+
+    #[test]
+    fn metrics_stop_at_epochs() {
+        let config = DeepQLearningConfig {
+            replay_size: 8,
+            nn_batch_size: 8,
+            ..Default::default()
+        };
+        let strategy = DecliningRandom::new(10, 0.1);
+        let agent = DeepQLearning::new(
+            |shape: &Vec<usize>, lr| Mlp::new(shape, lr),
+            &[4],
+            config,
+            strategy,
+            &mut CartPole::default(),
+        );
+        //every environment finishes on every step, so 4 epochs finish together
+        let envs = VecEnv::from_fn(4, || TimeLimit::new(CartPole::default(), 1));
+        let (_, metrics) = Runner::run_vec(agent, envs, 6);
+        assert_eq!(metrics.epochs(), 6);
+        assert_eq!(metrics.episode_lengths, vec![1; 6]);
+    }
+}
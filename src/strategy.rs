@@ -10,4 +10,27 @@ pub trait ExploreStrategy<A> {
         best: Option<A>, //best based on qlearning etc.
         progress: Progress,
     ) -> A;
+
+    ///Current chance of exploring, if the strategy has one. Used for metrics
+    fn exploration(&self) -> Option<f64> {
+        None
+    }
+
+    ///Values needed to restore the strategy later e.g. how far through exploring we are
+    fn snapshot(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    ///Restore values saved with `snapshot`, keeping settings the strategy was made with.
+    ///Fails if they were saved by a different kind of strategy
+    fn restore(&mut self, snapshot: &[f64]) -> Result<(), String> {
+        if snapshot.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected no strategy values, found {}",
+                snapshot.len()
+            ))
+        }
+    }
 }
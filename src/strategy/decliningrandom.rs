@@ -42,4 +42,45 @@ impl<A: Clone> ExploreStrategy<A> for DecliningRandom {
             (false, Some(best)) => best,
         }
     }
+
+    fn exploration(&self) -> Option<f64> {
+        Some(self.exploration_rate())
+    }
+
+    fn snapshot(&self) -> Vec<f64> {
+        vec![self.total as f64, self.current as f64, self.min_exploration]
+    }
+
+    /// Only restores how far through exploring we are. `total` and `min_exploration` are kept from `new`,
+    /// so a copy made with different values, e.g. perturbed by `Schedule::pbt`, keeps them
+    fn restore(&mut self, snapshot: &[f64]) -> Result<(), String> {
+        let [_total, current, _min_exploration] = snapshot else {
+            return Err(format!(
+                "expected 3 strategy values, found {}",
+                snapshot.len()
+            ));
+        };
+        self.current = *current as usize;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_keeps_settings_from_new() {
+        let mut trained = DecliningRandom::new(100, 0.1);
+        trained.current = 40;
+        let mut copy = DecliningRandom::new(200, 0.5);
+        ExploreStrategy::<u8>::restore(&mut copy, &ExploreStrategy::<u8>::snapshot(&trained))
+            .unwrap();
+        assert_eq!(
+            (copy.total, copy.current, copy.min_exploration),
+            (200, 40, 0.5)
+        );
+        assert_eq!(copy.exploration_rate(), 0.8);
+        assert!(ExploreStrategy::<u8>::restore(&mut copy, &[1.]).is_err());
+    }
 }
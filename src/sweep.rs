@@ -0,0 +1,368 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{agent::Agent, environment::Environment, runner::Metrics, runner::Runner};
+
+mod schedule;
+
+pub use schedule::{
+    hyperband, successive_halving, HalvingConfig, HyperbandConfig, Member, ScheduleResults,
+};
+#[cfg(feature = "serde")]
+pub use schedule::{population_based, PbtConfig};
+
+/// Named hyperparameters to search over e.g.
+/// ```ignore
+/// let space = SearchSpace::new()
+///     .values("alpha", &[0.1, 0.2])
+///     .linear("gamma", 0.9, 0.99, 4)
+///     .log("nn_learning_rate", 0.001, 0.1, 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SearchSpace {
+    params: Vec<(String, Param)>,
+}
+
+#[derive(Clone, Debug)]
+enum Param {
+    Values(Vec<f64>),
+    /// `count` points for grid search, or anywhere between `min` and `max` for random search
+    Range {
+        min: f64,
+        max: f64,
+        count: usize,
+        log: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Search {
+    /// every combination
+    Grid,
+    /// `trials` random combinations
+    Random { trials: usize },
+}
+
+pub struct SweepConfig {
+    /// each trial is run once for each seed, from 0, and the scores averaged
+    pub seeds: usize,
+    pub epochs: usize,
+    /// number of runs at the same time
+    pub threads: usize,
+    /// seed used to pick combinations for random search
+    pub search_seed: u64,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            seeds: 3,
+            epochs: 1000,
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+            search_seed: 0,
+        }
+    }
+}
+
+/// Values of each hyperparameter for one trial
+#[derive(Clone, Debug, PartialEq)]
+pub struct Params {
+    values: Vec<(String, f64)>,
+}
+
+pub struct TrialResult {
+    pub params: Params,
+    /// score of each seed
+    pub scores: Vec<f64>,
+    pub mean: f64,
+    /// standard deviation of `scores`
+    pub std: f64,
+}
+
+/// Trials with the best mean score first. Trials which scored NaN are last
+pub struct SweepResults {
+    pub trials: Vec<TrialResult>,
+}
+
+impl SearchSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only these values
+    pub fn values(mut self, name: &str, values: &[f64]) -> Self {
+        assert!(!values.is_empty(), "{name} needs at least 1 value");
+        self.params
+            .push((name.to_string(), Param::Values(values.to_vec())));
+        self
+    }
+
+    /// Evenly spaced between `min` and `max`
+    pub fn linear(self, name: &str, min: f64, max: f64, count: usize) -> Self {
+        self.range(name, min, max, count, false)
+    }
+
+    /// Spaced by ratio between `min` and `max`, e.g. for learning rates. Both must be more than 0
+    pub fn log(self, name: &str, min: f64, max: f64, count: usize) -> Self {
+        assert!(min > 0. && max > 0., "{name} must be more than 0 for log");
+        self.range(name, min, max, count, true)
+    }
+
+    fn range(mut self, name: &str, min: f64, max: f64, count: usize, log: bool) -> Self {
+        assert!(count > 0, "{name} needs at least 1 value");
+        self.params.push((
+            name.to_string(),
+            Param::Range {
+                min,
+                max,
+                count,
+                log,
+            },
+        ));
+        self
+    }
+
+    /// Every combination of values
+    fn grid(&self) -> Vec<Params> {
+        let mut all = vec![Params { values: vec![] }];
+        for (name, param) in &self.params {
+            all = all
+                .into_iter()
+                .flat_map(|params| {
+                    param.grid_values().into_iter().map(move |x| {
+                        let mut params = params.clone();
+                        params.values.push((name.clone(), x));
+                        params
+                    })
+                })
+                .collect();
+        }
+        all
+    }
+
+    fn random(&self, trials: usize, rng: &fastrand::Rng) -> Vec<Params> {
+        (0..trials)
+            .map(|_| Params {
+                values: self
+                    .params
+                    .iter()
+                    .map(|(name, param)| (name.clone(), param.sample(rng)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl Param {
+    fn grid_values(&self) -> Vec<f64> {
+        match self {
+            Param::Values(values) => values.clone(),
+            Param::Range {
+                min,
+                max,
+                count,
+                log,
+            } => (0..*count)
+                .map(|i| {
+                    let fraction = if *count == 1 {
+                        0.
+                    } else {
+                        i as f64 / (count - 1) as f64
+                    };
+                    interpolate(*min, *max, fraction, *log)
+                })
+                .collect(),
+        }
+    }
+
+    /// Nearest allowed value to `value`: one of `Values`, or kept within the range
+    #[cfg(feature = "serde")]
+    fn nearest(&self, value: f64) -> f64 {
+        match self {
+            Param::Values(values) => values
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+                .unwrap_or(value),
+            Param::Range { min, max, .. } => value.clamp(min.min(*max), min.max(*max)),
+        }
+    }
+
+    fn sample(&self, rng: &fastrand::Rng) -> f64 {
+        match self {
+            Param::Values(values) => values[rng.usize(0..values.len())],
+            Param::Range { min, max, log, .. } => interpolate(*min, *max, rng.f64(), *log),
+        }
+    }
+}
+
+fn interpolate(min: f64, max: f64, fraction: f64, log: bool) -> f64 {
+    if log {
+        (min.ln() + (max.ln() - min.ln()) * fraction).exp()
+    } else {
+        min + (max - min) * fraction
+    }
+}
+
+impl Params {
+    /// Panics if there is no hyperparameter called `name`
+    pub fn get(&self, name: &str) -> f64 {
+        self.values
+            .iter()
+            .find(|x| x.0 == name)
+            .unwrap_or_else(|| panic!("no hyperparameter called {name}"))
+            .1
+    }
+
+    /// Rounded to the nearest whole number e.g. for layer sizes
+    pub fn get_usize(&self, name: &str) -> usize {
+        self.get(name).round() as usize
+    }
+
+    pub fn values(&self) -> &[(String, f64)] {
+        &self.values
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text: Vec<String> = self
+            .values
+            .iter()
+            .map(|(name, value)| format!("{name}={value:.4}"))
+            .collect();
+        write!(f, "{}", text.join(" "))
+    }
+}
+
+impl SweepResults {
+    /// Panics if there were no trials
+    pub fn best(&self) -> &TrialResult {
+        &self.trials[0]
+    }
+}
+
+impl Display for SweepResults {
+    /// Table with a column for each hyperparameter
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(first) = self.trials.first() else {
+            return writeln!(f, "no trials");
+        };
+        write!(f, "{:>4} {:>12} {:>10}", "rank", "mean", "std")?;
+        for (name, _) in &first.params.values {
+            write!(f, " {name:>12}")?;
+        }
+        writeln!(f)?;
+        for (rank, trial) in self.trials.iter().enumerate() {
+            write!(
+                f,
+                "{:>4} {:>12.4} {:>10.4}",
+                rank + 1,
+                trial.mean,
+                trial.std
+            )?;
+            for (_, value) in &trial.params.values {
+                write!(f, " {value:>12.4}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every trial from `space` once per seed, over `config.threads` threads.
+/// `build` creates the agent and environment for the given hyperparameters,
+/// and `score` rates the trained agent e.g. `|_, metrics| metrics.mean_return(100)`.
+/// The random seed is set before `build` so each seed gives a repeatable run
+pub fn run<S, A, AG, E>(
+    space: &SearchSpace,
+    search: Search,
+    config: &SweepConfig,
+    build: impl Fn(&Params) -> (AG, E) + Sync,
+    score: impl Fn(AG, &Metrics) -> f64 + Sync,
+) -> SweepResults
+where
+    AG: Agent<S, A>,
+    E: Environment<S, A>,
+{
+    let trials = match search {
+        Search::Grid => space.grid(),
+        Search::Random { trials } => {
+            space.random(trials, &fastrand::Rng::with_seed(config.search_seed))
+        }
+    };
+    let seeds = config.seeds.max(1);
+    //every trial and seed
+    let runs: Vec<(usize, u64)> = (0..trials.len())
+        .flat_map(|t| (0..seeds as u64).map(move |s| (t, s)))
+        .collect();
+    let scores = Mutex::new(vec![vec![]; trials.len()]);
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..config.threads.max(1).min(runs.len()) {
+            scope.spawn(|| loop {
+                let Some(&(trial, seed)) = runs.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                    return;
+                };
+                fastrand::seed(seed);
+                let (agent, environment) = build(&trials[trial]);
+                let (agent, metrics) = Runner::run_with_metrics(agent, environment, config.epochs);
+                let result = score(agent, &metrics);
+                scores.lock().unwrap()[trial].push((seed, result));
+            });
+        }
+    });
+
+    let mut results: Vec<TrialResult> = trials
+        .into_iter()
+        .zip(scores.into_inner().unwrap())
+        .map(|(params, mut scores)| {
+            //same order regardless of which thread finished first
+            scores.sort_by_key(|x| x.0);
+            let scores: Vec<f64> = scores.into_iter().map(|x| x.1).collect();
+            let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+            let variance =
+                scores.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / scores.len() as f64;
+            TrialResult {
+                params,
+                scores,
+                mean,
+                std: variance.sqrt(),
+            }
+        })
+        .collect();
+    results.sort_by(|a, b| best_first(a.mean, b.mean));
+    SweepResults { trials: results }
+}
+
+/// Orders scores highest first, with NaN last instead of above infinity as `total_cmp` would put it
+fn best_first(a: f64, b: f64) -> std::cmp::Ordering {
+    a.is_nan().cmp(&b.is_nan()).then(b.total_cmp(&a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nan_scores_are_last() {
+        let mut scores = [
+            1.,
+            f64::NAN,
+            f64::INFINITY,
+            -f64::NAN,
+            3.,
+            f64::NEG_INFINITY,
+        ];
+        scores.sort_by(|a, b| best_first(*a, *b));
+        assert_eq!(scores[..4], [f64::INFINITY, 3., 1., f64::NEG_INFINITY]);
+        assert!(scores[4..].iter().all(|x| x.is_nan()));
+    }
+}
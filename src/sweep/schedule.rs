@@ -0,0 +1,548 @@
+use std::{fmt::Display, sync::Mutex, thread};
+
+#[cfg(feature = "serde")]
+use crate::{agent::Checkpoint, persist::PersistError};
+use crate::{
+    agent::ParallelAgent,
+    environment::Environment,
+    progress::Progress,
+    runner::{Metrics, Runner},
+};
+
+use super::{best_first, Params, Search, SearchSpace};
+
+pub struct HalvingConfig {
+    /// epochs every trial trains for in the first round.
+    /// Each round this is multiplied by `eta`, so the remaining trials train for longer
+    pub epochs: usize,
+    /// keep the best 1 in `eta` trials after each round
+    pub eta: usize,
+    /// epochs run by `Runner::evaluate` to score each trial
+    pub eval_epochs: usize,
+    /// number of trials trained at the same time
+    pub threads: usize,
+    /// seed used to pick combinations for random search, and for training
+    pub seed: u64,
+}
+
+impl Default for HalvingConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 100,
+            eta: 2,
+            eval_epochs: 20,
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+            seed: 0,
+        }
+    }
+}
+
+pub struct HyperbandConfig {
+    /// epochs trained before the first cut in the bracket with the most trials.
+    /// The best trials of every bracket train for `epochs * eta ^ (brackets - 1)`
+    pub epochs: usize,
+    /// keep the best 1 in `eta` trials after each round
+    pub eta: usize,
+    /// number of runs of successive halving, each with fewer trials and fewer cuts than the one before.
+    /// The first trains `eta ^ (brackets - 1)` trials
+    pub brackets: usize,
+    /// epochs run by `Runner::evaluate` to score each trial
+    pub eval_epochs: usize,
+    /// number of trials trained at the same time
+    pub threads: usize,
+    /// seed used to pick hyperparameters, and for training
+    pub seed: u64,
+}
+
+impl Default for HyperbandConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 100,
+            eta: 3,
+            brackets: 3,
+            eval_epochs: 20,
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+            seed: 0,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub struct PbtConfig {
+    /// number of agents trained together
+    pub population: usize,
+    pub rounds: usize,
+    /// epochs trained each round
+    pub epochs: usize,
+    /// epochs run by `Runner::evaluate` to score each agent
+    pub eval_epochs: usize,
+    /// fraction of the population replaced each round by copies of the best fraction
+    pub replace: f64,
+    /// copied hyperparameters are multiplied by 1 + or - this, then moved to the nearest value in the search space
+    pub perturb: f64,
+    /// number of agents trained at the same time
+    pub threads: usize,
+    /// seed used to pick hyperparameters, and for training
+    pub seed: u64,
+}
+
+#[cfg(feature = "serde")]
+impl Default for PbtConfig {
+    fn default() -> Self {
+        Self {
+            population: 8,
+            rounds: 10,
+            epochs: 100,
+            eval_epochs: 20,
+            replace: 0.25,
+            perturb: 0.2,
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+            seed: 0,
+        }
+    }
+}
+
+/// A trained agent and the hyperparameters it ended with
+pub struct Member<AG> {
+    pub params: Params,
+    pub agent: AG,
+    /// mean return of the last evaluation
+    pub score: f64,
+    /// epochs trained
+    pub epochs: usize,
+    /// every training epoch
+    pub metrics: Metrics,
+}
+
+/// Members which trained for the most epochs first, then those with the best score. NaN scores are last
+pub struct ScheduleResults<AG> {
+    pub members: Vec<Member<AG>>,
+}
+
+/// Member still training, with its own environment and random state
+struct Running<AG, E> {
+    member: Member<AG>,
+    environment: E,
+    progress: Progress,
+    rng: u64,
+}
+
+impl<AG, E> Running<AG, E> {
+    fn new(params: Params, (agent, environment): (AG, E), rng: u64) -> Self {
+        Self {
+            member: Member {
+                params,
+                agent,
+                score: f64::NEG_INFINITY,
+                epochs: 0,
+                metrics: Metrics::default(),
+            },
+            environment,
+            progress: Progress {
+                epoch: 0,
+                epoch_step: 0,
+                cumulative_steps: 0,
+            },
+            rng,
+        }
+    }
+
+    /// Trains for `epochs` more, then scores with `Runner::evaluate`
+    fn round<S, A: Clone>(&mut self, epochs: usize, eval_epochs: usize)
+    where
+        AG: ParallelAgent<S, A>,
+        E: Environment<S, A>,
+    {
+        //own random state, so results do not depend on which thread runs this
+        fastrand::seed(self.rng);
+        let member = &mut self.member;
+        Runner::run_until(
+            &mut member.agent,
+            &mut self.environment,
+            self.progress.epoch + epochs,
+            &mut self.progress,
+            &mut member.metrics,
+        );
+        member.epochs = self.progress.epoch;
+        let evaluation = Runner::evaluate(&member.agent, &mut self.environment, eval_epochs);
+        member.score = evaluation.mean_return(eval_epochs);
+        self.rng = fastrand::get_seed();
+    }
+}
+
+/// Runs `f` on every item, over `threads` threads
+fn for_each_parallel<T: Send>(items: Vec<&mut T>, threads: usize, f: impl Fn(&mut T) + Sync) {
+    let queue = Mutex::new(items.into_iter());
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let Some(item) = queue.lock().unwrap().next() else {
+                    return;
+                };
+                f(item);
+            });
+        }
+    });
+}
+
+/// Trains every trial from `space` a little, then keeps training only the best 1 in `config.eta`,
+/// until 1 is left. This spends most epochs on the promising hyperparameters.
+/// `build` creates the agent and environment for the given hyperparameters.
+/// Returns every trial, including those stopped early, which rank below the trials that trained longer
+pub fn successive_halving<S, A, AG, E>(
+    space: &SearchSpace,
+    search: Search,
+    config: &HalvingConfig,
+    build: impl Fn(&Params) -> (AG, E),
+) -> ScheduleResults<AG>
+where
+    A: Clone,
+    AG: ParallelAgent<S, A> + Send,
+    E: Environment<S, A> + Send,
+{
+    let rng = fastrand::Rng::with_seed(config.seed);
+    let trials = match search {
+        Search::Grid => space.grid(),
+        Search::Random { trials } => space.random(trials, &rng),
+    };
+    let mut running: Vec<Running<AG, E>> = trials
+        .into_iter()
+        .map(|params| {
+            let built = build(&params);
+            Running::new(params, built, rng.u64(..))
+        })
+        .collect();
+
+    let eta = config.eta.max(2);
+    halve(
+        &mut running,
+        eta,
+        usize::MAX,
+        config.eval_epochs,
+        config.threads,
+        |round| config.epochs * eta.pow(round as u32),
+    );
+    ScheduleResults::new(running.into_iter().map(|x| x.member).collect())
+}
+
+/// Hyperband: runs `config.brackets` rounds of successive halving on random trials from `space`, from many trials
+/// trained briefly before the first cut, to a few trials trained fully without any cut.
+/// This hedges against good hyperparameters which start slowly, which successive halving alone would stop early.
+/// The best trials of every bracket train for the same number of epochs, so they rank against each other by score.
+/// `build` creates the agent and environment for the given hyperparameters.
+/// Returns every trial, including those stopped early, which rank below the trials that trained longer
+pub fn hyperband<S, A, AG, E>(
+    space: &SearchSpace,
+    config: &HyperbandConfig,
+    build: impl Fn(&Params) -> (AG, E),
+) -> ScheduleResults<AG>
+where
+    A: Clone,
+    AG: ParallelAgent<S, A> + Send,
+    E: Environment<S, A> + Send,
+{
+    let rng = fastrand::Rng::with_seed(config.seed);
+    let eta = config.eta.max(2);
+    let brackets = config.brackets.max(1);
+    let mut members = vec![];
+    //most trials first, cut s times
+    for s in (0..brackets).rev() {
+        let trials = (brackets * eta.pow(s as u32)).div_ceil(s + 1);
+        let mut running: Vec<Running<AG, E>> = space
+            .random(trials, &rng)
+            .into_iter()
+            .map(|params| {
+                let built = build(&params);
+                Running::new(params, built, rng.u64(..))
+            })
+            .collect();
+        //trained for `first` epochs by the first cut, and `eta` times as many in total by each cut after
+        let first = config.epochs * eta.pow((brackets - 1 - s) as u32);
+        halve(
+            &mut running,
+            eta,
+            s + 1,
+            config.eval_epochs,
+            config.threads,
+            |round| match round {
+                0 => first,
+                _ => first * eta.pow(round as u32 - 1) * (eta - 1),
+            },
+        );
+        members.extend(running.into_iter().map(|x| x.member));
+    }
+    ScheduleResults::new(members)
+}
+
+/// Trains and scores every trial in `running` for `epochs(0)` more epochs, then keeps training only the best 1 in `eta`
+/// for `epochs(1)` more, and so on for `rounds` rounds or until 1 is left. Leaves the trials trained longest first,
+/// each round sorted best first
+fn halve<S, A, AG, E>(
+    running: &mut [Running<AG, E>],
+    eta: usize,
+    rounds: usize,
+    eval_epochs: usize,
+    threads: usize,
+    epochs: impl Fn(usize) -> usize,
+) where
+    A: Clone,
+    AG: ParallelAgent<S, A> + Send,
+    E: Environment<S, A> + Send,
+{
+    let mut remaining = running.len();
+    for round in 0..rounds {
+        if remaining == 0 {
+            return;
+        }
+        //best first, so the remaining are always at the start
+        let current: Vec<&mut Running<AG, E>> = running.iter_mut().take(remaining).collect();
+        let epochs = epochs(round);
+        for_each_parallel(current, threads, |x| x.round(epochs, eval_epochs));
+        running[..remaining].sort_by(|a, b| best_first(a.member.score, b.member.score));
+        if remaining == 1 {
+            return;
+        }
+        remaining = remaining.div_ceil(eta);
+    }
+}
+
+/// Population based training: trains `config.population` agents with random hyperparameters from `space`,
+/// and after each round replaces the worst with copies of the best, with their hyperparameters perturbed.
+/// Copies are made by creating a new agent with `build` and loading a checkpoint of the better agent
+#[cfg(feature = "serde")]
+pub fn population_based<S, A, AG, E>(
+    space: &SearchSpace,
+    config: &PbtConfig,
+    build: impl Fn(&Params) -> (AG, E),
+) -> Result<ScheduleResults<AG>, PersistError>
+where
+    A: Clone,
+    AG: ParallelAgent<S, A> + Checkpoint + Send,
+    E: Environment<S, A> + Send,
+{
+    let rng = fastrand::Rng::with_seed(config.seed);
+    let mut running: Vec<Running<AG, E>> = space
+        .random(config.population, &rng)
+        .into_iter()
+        .map(|params| {
+            let built = build(&params);
+            Running::new(params, built, rng.u64(..))
+        })
+        .collect();
+
+    let count = running.len();
+    let replace = ((count as f64 * config.replace).round() as usize).min(count / 2);
+    for round in 0..config.rounds {
+        for_each_parallel(running.iter_mut().collect(), config.threads, |x| {
+            x.round(config.epochs, config.eval_epochs)
+        });
+        running.sort_by(|a, b| best_first(a.member.score, b.member.score));
+        if round == config.rounds - 1 {
+            break;
+        }
+        //exploit the best and explore around their hyperparameters
+        for worst in count - replace..count {
+            let best = &running[rng.usize(0..replace)];
+            let mut checkpoint = vec![];
+            best.member.agent.save_checkpoint(&mut checkpoint)?;
+            let params = best.member.params.perturbed(space, config.perturb, &rng);
+            let (progress, metrics) = (best.progress, best.member.metrics.clone());
+
+            let built = build(&params);
+            let mut copy = Running::new(params, built, rng.u64(..));
+            copy.member
+                .agent
+                .load_checkpoint(&mut checkpoint.as_slice())?;
+            copy.member.epochs = progress.epoch;
+            copy.member.metrics = metrics;
+            copy.progress = progress;
+            running[worst] = copy;
+        }
+    }
+    Ok(ScheduleResults::new(
+        running.into_iter().map(|x| x.member).collect(),
+    ))
+}
+
+#[cfg(feature = "serde")]
+impl Params {
+    /// every value multiplied by 1 + or - `perturb`, then moved to the nearest value allowed by `space`
+    fn perturbed(&self, space: &SearchSpace, perturb: f64, rng: &fastrand::Rng) -> Self {
+        Self {
+            values: self
+                .values
+                .iter()
+                .zip(&space.params)
+                .map(|((name, value), (_, param))| {
+                    let factor = if rng.bool() {
+                        1. + perturb
+                    } else {
+                        1. - perturb
+                    };
+                    (name.clone(), param.nearest(value * factor))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<AG> ScheduleResults<AG> {
+    fn new(mut members: Vec<Member<AG>>) -> Self {
+        //trials stopped early by successive halving can score well on fewer epochs, so they rank below
+        members.sort_by(|a, b| b.epochs.cmp(&a.epochs).then(best_first(a.score, b.score)));
+        Self { members }
+    }
+
+    /// Panics if there were no members
+    pub fn best(&self) -> &Member<AG> {
+        &self.members[0]
+    }
+}
+
+impl<AG> Display for ScheduleResults<AG> {
+    /// Table with a column for each hyperparameter
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(first) = self.members.first() else {
+            return writeln!(f, "no members");
+        };
+        write!(f, "{:>4} {:>12} {:>8}", "rank", "score", "epochs")?;
+        for (name, _) in &first.params.values {
+            write!(f, " {name:>12}")?;
+        }
+        writeln!(f)?;
+        for (rank, member) in self.members.iter().enumerate() {
+            write!(
+                f,
+                "{:>4} {:>12.4} {:>8}",
+                rank + 1,
+                member.score,
+                member.epochs
+            )?;
+            for (_, value) in &member.params.values {
+                write!(f, " {value:>12.4}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{agent::qlearning::QLearning, strategy::decliningrandom::DecliningRandom};
+
+    fn member(score: f64, epochs: usize) -> Member<()> {
+        Member {
+            params: Params { values: vec![] },
+            agent: (),
+            score,
+            epochs,
+            metrics: Metrics::default(),
+        }
+    }
+
+    #[test]
+    fn longest_trained_ranks_first() {
+        let results = ScheduleResults::new(vec![
+            member(9., 100),
+            member(f64::NAN, 400),
+            member(2., 400),
+            member(5., 200),
+            member(3., 400),
+        ]);
+        let ranked: Vec<(f64, usize)> = results
+            .members
+            .iter()
+            .map(|x| (x.score, x.epochs))
+            .collect();
+        assert_eq!(ranked[..2], [(3., 400), (2., 400)]);
+        assert!(ranked[2].0.is_nan());
+        assert_eq!(ranked[3..], [(5., 200), (9., 100)]);
+        assert_eq!(results.best().score, 3.);
+    }
+
+    /// Reward of 1 for action 1, for 5 steps
+    struct Pick;
+
+    impl Environment<u8, u8> for Pick {
+        fn state(&self) -> u8 {
+            0
+        }
+        fn reset(&mut self, _epoch: usize) {}
+        fn all_actions(&self) -> Vec<u8> {
+            vec![0, 1]
+        }
+        fn take_action_get_reward(&mut self, action: &u8) -> f64 {
+            *action as f64
+        }
+        fn should_stop(&mut self, step: usize) -> bool {
+            step >= 5
+        }
+    }
+
+    #[test]
+    fn hyperband_brackets_end_with_same_epochs() {
+        let space = SearchSpace::new().linear("alpha", 0.1, 0.9, 3);
+        let config = HyperbandConfig {
+            epochs: 2,
+            eta: 2,
+            brackets: 3,
+            eval_epochs: 1,
+            threads: 2,
+            seed: 1,
+        };
+        let results = hyperband(&space, &config, |params| {
+            let agent = QLearning::new(params.get("alpha"), 0.5, DecliningRandom::new(4, 0.));
+            (agent, Pick)
+        });
+        let epochs: Vec<usize> = results.members.iter().map(|x| x.epochs).collect();
+        //brackets of 4 trials cut twice, 3 trials cut once and 3 trials not cut
+        assert_eq!(epochs, [8, 8, 8, 8, 8, 8, 4, 4, 2, 2]);
+        assert!(results.members[..6]
+            .windows(2)
+            .all(|x| x[0].score >= x[1].score));
+        assert!(results
+            .members
+            .iter()
+            .all(|x| x.metrics.epochs() == x.epochs));
+    }
+
+    #[test]
+    fn halving_trains_the_best_for_longer() {
+        let space = SearchSpace::new().linear("alpha", 0.1, 0.9, 5);
+        let config = HalvingConfig {
+            epochs: 3,
+            eta: 2,
+            eval_epochs: 1,
+            threads: 2,
+            seed: 1,
+        };
+        let results = successive_halving(&space, Search::Grid, &config, |params| {
+            let agent = QLearning::new(params.get("alpha"), 0.5, DecliningRandom::new(4, 0.));
+            (agent, Pick)
+        });
+        let epochs: Vec<usize> = results.members.iter().map(|x| x.epochs).collect();
+        //5 trials for 3 epochs, 3 for 6 more, 2 for 12 more, then 1 for 24 more
+        assert_eq!(epochs, [45, 21, 9, 3, 3]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn perturbed_values_are_allowed() {
+        let space = SearchSpace::new()
+            .values("batch", &[16., 32., 64.])
+            .linear("gamma", 0.5, 0.9, 2);
+        let params = Params {
+            values: vec![("batch".to_string(), 64.), ("gamma".to_string(), 0.9)],
+        };
+        let rng = fastrand::Rng::with_seed(3);
+        for _ in 0..20 {
+            let perturbed = params.perturbed(&space, 0.6, &rng);
+            //64 * 0.4 is nearest 32, and 64 * 1.6 is above the largest value
+            assert!([32., 64.].contains(&perturbed.get("batch")));
+            //ranges are clamped at both ends
+            assert!([0.5, 0.9].contains(&perturbed.get("gamma")));
+        }
+    }
+}